@@ -0,0 +1,153 @@
+//! Chimes (earcons) played right before an announcement
+//!
+//! A chime is either a short sequence of generated sine tones or a WAV file.
+//! Both are rendered as mono samples at the voice model's sample rate so they
+//! can be joined with the synthesized speech.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use quarm_config::{Chime, Tone};
+
+// Peak amplitude of generated tones before normalization
+const TONE_AMPLITUDE: f32 = 0.5;
+
+// Fade in/out applied to each tone to avoid clicks
+const TONE_FADE_SECS: f32 = 0.005;
+
+// Silence between consecutive tones
+const TONE_GAP_SECS: f32 = 0.03;
+
+/// Renders a chime as mono samples at the given sample rate
+/// WAV files are mixed down to mono and resampled when needed
+pub fn render_chime(chime: &Chime, sample_rate: u32) -> Result<Vec<f32>> {
+    match chime {
+        Chime::Tones(tones) => Ok(generate_tones(tones, sample_rate)),
+        Chime::File(path) => load_wav(Path::new(path), sample_rate),
+    }
+}
+
+/// Generates a sequence of faded sine tones separated by short gaps
+pub fn generate_tones(tones: &[Tone], sample_rate: u32) -> Vec<f32> {
+    let rate = sample_rate as f32;
+    let fade_len = (rate * TONE_FADE_SECS) as usize;
+    let gap_len = (rate * TONE_GAP_SECS) as usize;
+    let mut samples = Vec::new();
+
+    for (index, tone) in tones.iter().enumerate() {
+        if index > 0 {
+            samples.extend(std::iter::repeat_n(0.0, gap_len));
+        }
+
+        let len = (rate * tone.duration_ms as f32 / 1000.0) as usize;
+        let step = tone.frequency_hz * std::f32::consts::TAU / rate;
+        samples.extend((0..len).map(|i| {
+            let fade_in = (i as f32 / fade_len.max(1) as f32).min(1.0);
+            let fade_out = ((len - i) as f32 / fade_len.max(1) as f32).min(1.0);
+            TONE_AMPLITUDE * fade_in * fade_out * (i as f32 * step).sin()
+        }));
+    }
+
+    samples
+}
+
+/// Loads a WAV file as mono samples at the given sample rate
+fn load_wav(path: &Path, sample_rate: u32) -> Result<Vec<f32>> {
+    let reader = hound::WavReader::open(path)
+        .context(format!("Failed to open chime file: {}", path.display()))?;
+    let spec = reader.spec();
+    let channels = usize::from(spec.channels.max(1));
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .collect::<Result<_, _>>()
+            .context("Failed to read chime samples")?,
+        hound::SampleFormat::Int => {
+            if spec.bits_per_sample == 0 || spec.bits_per_sample > 32 {
+                bail!("Unsupported chime bit depth: {}", spec.bits_per_sample);
+            }
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .context("Failed to read chime samples")?
+        }
+    };
+
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(resample(&mono, spec.sample_rate, sample_rate))
+}
+
+/// Resamples mono audio with linear interpolation
+fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let len = (samples.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = samples[index.min(samples.len() - 1)];
+            let next = samples[(index + 1).min(samples.len() - 1)];
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_tones_length_and_amplitude() {
+        let tones = [Tone::new(880.0, 100), Tone::new(660.0, 50)];
+        let samples = generate_tones(&tones, 22050);
+
+        // 100ms + 30ms gap + 50ms
+        let expected = 2205 + 661 + 1102;
+        assert!((samples.len() as i64 - expected).abs() <= 2);
+        assert!(samples.iter().all(|s| s.abs() <= TONE_AMPLITUDE));
+        // Faded in from silence
+        assert!(samples[0].abs() < 0.01);
+    }
+
+    #[test]
+    fn test_load_wav_mixes_down_and_resamples() {
+        let path = std::env::temp_dir().join("quarm_test_chime.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..4410 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+            writer.write_sample(0_i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let samples =
+            render_chime(&Chime::File(path.to_string_lossy().to_string()), 22050).unwrap();
+        assert_eq!(samples.len(), 2205);
+        assert!((samples[100] - 0.25).abs() < 0.01);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_missing_chime_file_errors() {
+        let result = render_chime(&Chime::File("./nonexistent/chime.wav".to_string()), 22050);
+        assert!(result.is_err());
+    }
+}
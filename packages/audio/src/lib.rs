@@ -3,9 +3,15 @@ use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use piper_rs::PiperModel;
 use piper_rs::synth::PiperSpeechSynthesizer;
+use quarm_config::{AudioSettings, Lexicon, MessageConfig, TriggerCategory};
 use tokio::sync::{Mutex, Semaphore};
 
+pub mod chime;
+pub mod loudness;
+pub mod pan;
+
 // SamplesBuffer is only used in production builds for audio playback
 #[cfg(not(test))]
 use rodio::buffer::SamplesBuffer;
@@ -13,11 +19,74 @@ use rodio::buffer::SamplesBuffer;
 // Audio-related constants
 pub static SPEAKER_ID: i64 = 4;
 
+// Silence between a chime and the speech that follows it
+const CHIME_GAP_SECS: f32 = 0.06;
+
+/// Rendering options that change an announcement's audio besides its text
+/// Part of the cache key, so each style of the same text is cached separately
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AnnouncementStyle {
+    /// Category whose chime is played right before the speech
+    pub category: Option<TriggerCategory>,
+    /// Stereo position in hundredths, from -100 (left) to 100 (right)
+    /// Stored as an integer so styles can be hashed as cache keys
+    pub pan: i8,
+    /// Piper speaker id of the voice; the default voice when unset
+    pub speaker: Option<i64>,
+}
+
+impl AnnouncementStyle {
+    /// Returns the style configured on a message
+    pub fn for_message(message: &MessageConfig) -> Self {
+        Self {
+            category: message.category(),
+            pan: (message.pan().clamp(-1.0, 1.0) * 100.0).round() as i8,
+            speaker: None,
+        }
+    }
+}
+
+/// Cache key: lexicon-rewritten text plus rendering style
+type CacheKey = (String, AnnouncementStyle);
+
+/// Rendered chime samples by trigger category
+type ChimeMap = HashMap<TriggerCategory, Arc<Vec<f32>>>;
+
+/// Piper synthesizer together with its model, so the voice can be switched per clip
+struct Synthesizer {
+    model: Arc<dyn PiperModel + Send + Sync>,
+    speech: PiperSpeechSynthesizer,
+}
+
+impl Synthesizer {
+    fn new(model: Arc<dyn PiperModel + Send + Sync>) -> Result<Self> {
+        Ok(Self {
+            speech: PiperSpeechSynthesizer::new(Arc::clone(&model))
+                .context("Failed to create PiperSpeechSynthesizer")?,
+            model,
+        })
+    }
+}
+
 /// TTS Engine for synthesizing and playing audio announcements
 pub struct TtsEngine {
-    synthesizer: Arc<Mutex<PiperSpeechSynthesizer>>,
+    synthesizer: Arc<Mutex<Synthesizer>>,
     audio_semaphore: Arc<Semaphore>,
-    audio_cache: Arc<HashMap<String, Arc<Vec<f32>>>>,
+    /// Synthesized samples keyed by the lexicon-rewritten text and style
+    audio_cache: Arc<HashMap<CacheKey, Arc<Vec<f32>>>>,
+    /// Pronunciation overrides applied to text before synthesis
+    lexicon: Arc<Lexicon>,
+    /// Loudness normalization and chime settings for synthesized clips
+    clip_renderer: ClipRenderer,
+}
+
+/// Post-processing applied to synthesized speech before it is cached
+#[derive(Clone)]
+struct ClipRenderer {
+    settings: Arc<AudioSettings>,
+    chimes: Arc<ChimeMap>,
+    /// Output sample rate of the loaded Piper model (Hz)
+    sample_rate: u32,
 }
 
 impl Clone for TtsEngine {
@@ -26,6 +95,8 @@ impl Clone for TtsEngine {
             synthesizer: Arc::clone(&self.synthesizer),
             audio_semaphore: Arc::clone(&self.audio_semaphore),
             audio_cache: Arc::clone(&self.audio_cache),
+            lexicon: Arc::clone(&self.lexicon),
+            clip_renderer: self.clip_renderer.clone(),
         }
     }
 }
@@ -41,15 +112,12 @@ impl TtsEngine {
                 .context("Failed to spawn blocking task for model loading")?
                 .context("Failed to load Piper model from config path")?;
 
-        // Set speaker ID
-        model.set_speaker(SPEAKER_ID);
+        // Remember the model's native sample rate for playback and WAV export
+        let sample_rate = model_sample_rate(model.as_ref())?;
 
         // Wrap synthesizer in Arc<Mutex> for thread-safe sharing
         // Mutex is needed because espeak-ng (used by Piper) is not thread-safe
-        let synthesizer = Arc::new(Mutex::new(
-            PiperSpeechSynthesizer::new(model)
-                .context("Failed to create PiperSpeechSynthesizer")?,
-        ));
+        let synthesizer = Arc::new(Mutex::new(Synthesizer::new(model)?));
 
         // Create semaphore for limiting concurrent announcements
         let audio_semaphore = Arc::new(Semaphore::new(1));
@@ -61,6 +129,8 @@ impl TtsEngine {
             synthesizer,
             audio_semaphore,
             audio_cache,
+            lexicon: Arc::new(Lexicon::default()),
+            clip_renderer: ClipRenderer::new(AudioSettings::default(), sample_rate)?,
         })
     }
 
@@ -76,11 +146,10 @@ impl TtsEngine {
         let model = piper_rs::from_config_path(&config_path)
             .context("Failed to load Piper model for mock - model file may not exist")?;
 
-        model.set_speaker(SPEAKER_ID);
+        let sample_rate = model_sample_rate(model.as_ref())?;
 
         let synthesizer = Arc::new(Mutex::new(
-            PiperSpeechSynthesizer::new(model)
-                .context("Failed to create PiperSpeechSynthesizer for mock")?,
+            Synthesizer::new(model).context("Failed to create synthesizer for mock")?,
         ));
 
         let audio_semaphore = Arc::new(Semaphore::new(1));
@@ -92,22 +161,63 @@ impl TtsEngine {
             synthesizer,
             audio_semaphore,
             audio_cache,
+            lexicon: Arc::new(Lexicon::default()),
+            clip_renderer: ClipRenderer::new(AudioSettings::default(), sample_rate)?,
         })
     }
 
+    /// Sets the pronunciation lexicon applied before synthesis
+    /// Should be called before precache() so cached entries use the rewritten text
+    pub fn set_lexicon(&mut self, lexicon: Lexicon) {
+        self.lexicon = Arc::new(lexicon);
+    }
+
+    /// Sets the loudness normalization and chimes applied to synthesized clips
+    /// Loads chime files from disk; should be called before precache() so cached entries use them
+    pub async fn set_audio_settings(&mut self, settings: AudioSettings) -> Result<()> {
+        let sample_rate = self.clip_renderer.sample_rate;
+        self.clip_renderer =
+            tokio::task::spawn_blocking(move || ClipRenderer::new(settings, sample_rate))
+                .await
+                .context("Failed to spawn blocking task for chime loading")?
+                .context("Failed to load chimes")?;
+        Ok(())
+    }
+
     /// Pre-synthesizes audio for all given texts and caches them for fast playback
     /// Should be called at startup before any announce() calls
-    pub async fn precache(&mut self, texts: impl IntoIterator<Item = impl AsRef<str>>) -> Result<()> {
+    pub async fn precache(
+        &mut self,
+        texts: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<()> {
+        let default_style = AnnouncementStyle::default();
+        self.precache_styled(
+            texts
+                .into_iter()
+                .map(|text| (text.as_ref().to_string(), default_style)),
+        )
+        .await
+    }
+
+    /// Pre-synthesizes audio for texts rendered with specific styles (e.g. chimes)
+    pub async fn precache_styled(
+        &mut self,
+        items: impl IntoIterator<Item = (String, AnnouncementStyle)>,
+    ) -> Result<()> {
         let synth = Arc::clone(&self.synthesizer);
-        let texts: Vec<String> = texts.into_iter().map(|t| t.as_ref().to_string()).collect();
+        let renderer = self.clip_renderer.clone();
+        let keys: Vec<CacheKey> = items
+            .into_iter()
+            .map(|(text, style)| (self.lexicon.apply(&text), style))
+            .collect();
 
         // Synthesize all texts in blocking thread (espeak-ng is not thread-safe)
         let samples_map = tokio::task::spawn_blocking(move || {
             let synth_guard = synth.blocking_lock();
             let mut map = HashMap::new();
-            for text in texts {
-                let samples = synthesize_audio(&synth_guard, &text)?;
-                map.insert(text, Arc::new(samples));
+            for (text, style) in keys {
+                let samples = renderer.render(&synth_guard, &text, style)?;
+                map.insert((text, style), Arc::new(samples));
             }
             Ok::<_, anyhow::Error>(map)
         })
@@ -123,25 +233,21 @@ impl TtsEngine {
         Ok(())
     }
 
+    /// Returns the output sample rate of the loaded voice model in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.clip_renderer.sample_rate
+    }
+
     /// Announces a message via TTS in a non-blocking way
     pub async fn announce(&self, text: &str) -> Result<()> {
-        // 1. Check cache first, fallback to synthesis if not cached
-        let samples = if let Some(cached) = self.audio_cache.get(text) {
-            // Cache hit - just clone the Arc reference (cheap)
-            Arc::clone(cached)
-        } else {
-            // Cache miss - synthesize on demand (original behavior)
-            let synth = Arc::clone(&self.synthesizer);
-            let text = text.to_string();
-            let samples = tokio::task::spawn_blocking(move || {
-                let synth_guard = synth.blocking_lock();
-                synthesize_audio(&synth_guard, &text)
-            })
+        self.announce_styled(text, AnnouncementStyle::default())
             .await
-            .context("Failed to spawn blocking task for synthesis")?
-            .context("TTS synthesis failed")?;
-            Arc::new(samples)
-        };
+    }
+
+    /// Announces a message with a rendering style (e.g. a category chime)
+    pub async fn announce_styled(&self, text: &str, style: AnnouncementStyle) -> Result<()> {
+        // 1. Check cache first, fallback to synthesis if not cached
+        let samples = self.samples_for(text, style).await?;
 
         // 2. Acquire semaphore permit ONLY for playback to prevent audio overlap
         // This allows next announcement to start synthesizing while current one plays
@@ -154,21 +260,152 @@ impl TtsEngine {
         // 3. Play audio (blocking rodio operations)
         // Note: We need to convert Arc<Vec<f32>> to Vec<f32> for play_audio
         let samples_vec = (*samples).clone();
-        tokio::task::spawn_blocking(move || play_audio(samples_vec))
+        let sample_rate = self.sample_rate();
+        tokio::task::spawn_blocking(move || play_audio(samples_vec, sample_rate))
             .await
             .context("Failed to spawn blocking task for audio playback")?
             .context("Audio playback failed")?;
 
         Ok(())
     }
+
+    /// Renders a message to a WAV file instead of playing it
+    /// Uses cached audio when available, so the file matches what `announce()` would play
+    pub async fn render_to_wav(&self, text: &str, path: impl AsRef<Path>) -> Result<()> {
+        self.render_styled_to_wav(text, AnnouncementStyle::default(), path)
+            .await
+    }
+
+    /// Renders a message with a rendering style to a WAV file
+    pub async fn render_styled_to_wav(
+        &self,
+        text: &str,
+        style: AnnouncementStyle,
+        path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let samples = self.samples_for(text, style).await?;
+        let path = path.as_ref().to_path_buf();
+        let sample_rate = self.sample_rate();
+
+        tokio::task::spawn_blocking(move || write_wav(&path, sample_rate, &samples))
+            .await
+            .context("Failed to spawn blocking task for WAV export")?
+            .context("WAV export failed")?;
+
+        Ok(())
+    }
+
+    /// Returns the samples for a text, from the cache or synthesized on demand
+    /// The lexicon is applied first, so the rewritten text is part of the cache key
+    async fn samples_for(&self, text: &str, style: AnnouncementStyle) -> Result<Arc<Vec<f32>>> {
+        let key = (self.lexicon.apply(text), style);
+        if let Some(cached) = self.audio_cache.get(&key) {
+            // Cache hit - just clone the Arc reference (cheap)
+            return Ok(Arc::clone(cached));
+        }
+
+        // Cache miss - synthesize on demand (original behavior)
+        let synth = Arc::clone(&self.synthesizer);
+        let renderer = self.clip_renderer.clone();
+        let samples = tokio::task::spawn_blocking(move || {
+            let synth_guard = synth.blocking_lock();
+            renderer.render(&synth_guard, &key.0, key.1)
+        })
+        .await
+        .context("Failed to spawn blocking task for synthesis")?
+        .context("TTS synthesis failed")?;
+
+        Ok(Arc::new(samples))
+    }
 }
 
 // Synchronous helper functions (run in blocking thread pool)
 
-/// Synthesizes audio from text using Piper TTS (synchronous, CPU-bound)
-fn synthesize_audio(synth: &PiperSpeechSynthesizer, text: &str) -> Result<Vec<f32>> {
+/// Reads the native output sample rate from a loaded Piper model
+fn model_sample_rate(model: &(dyn PiperModel + Send + Sync)) -> Result<u32> {
+    let info = model
+        .audio_output_info()
+        .context("Failed to read audio output info from Piper model")?;
+    u32::try_from(info.sample_rate).context("Piper model sample rate out of range")
+}
+
+impl ClipRenderer {
+    /// Renders the configured chimes and prepares for clip rendering (synchronous, may read files)
+    fn new(settings: AudioSettings, sample_rate: u32) -> Result<Self> {
+        let mut chimes = HashMap::new();
+        for (category, chime) in &settings.chimes {
+            let mut samples = chime::render_chime(chime, sample_rate)
+                .context(format!("Failed to render {:?} chime", category))?;
+            normalize_clip(&mut samples, &settings, sample_rate);
+            chimes.insert(*category, Arc::new(samples));
+        }
+
+        Ok(Self {
+            settings: Arc::new(settings),
+            chimes: Arc::new(chimes),
+            sample_rate,
+        })
+    }
+
+    /// Synthesizes speech, normalizes it, prepends the style's chime and pans
+    /// the result into interleaved stereo (synchronous, CPU-bound)
+    fn render(
+        &self,
+        synth: &Synthesizer,
+        text: &str,
+        style: AnnouncementStyle,
+    ) -> Result<Vec<f32>> {
+        let mut speech = synthesize_audio(synth, text, style.speaker.unwrap_or(SPEAKER_ID))?;
+        normalize_clip(&mut speech, &self.settings, self.sample_rate);
+
+        let chime = style
+            .category
+            .and_then(|category| self.chimes.get(&category));
+        let mono = match chime {
+            Some(chime) => {
+                let gap_len = (self.sample_rate as f32 * CHIME_GAP_SECS) as usize;
+                let mut samples = Vec::with_capacity(chime.len() + gap_len + speech.len());
+                samples.extend_from_slice(chime);
+                samples.extend(std::iter::repeat_n(0.0, gap_len));
+                samples.append(&mut speech);
+                samples
+            }
+            None => speech,
+        };
+
+        Ok(pan::pan_to_stereo(&mono, f32::from(style.pan) / 100.0))
+    }
+}
+
+/// Applies loudness normalization when enabled in the settings
+fn normalize_clip(samples: &mut [f32], settings: &AudioSettings, sample_rate: u32) {
+    if settings.normalize {
+        loudness::normalize(
+            samples,
+            sample_rate,
+            settings.target_loudness_db,
+            settings.peak_ceiling_db,
+        );
+    }
+}
+
+/// Synthesizes audio from text using Piper TTS with the given voice (synchronous, CPU-bound)
+/// Single-speaker models (like the bundled voice) have no speaker ids, so `speaker` only
+/// applies to multi-speaker models
+fn synthesize_audio(synth: &Synthesizer, text: &str, speaker: i64) -> Result<Vec<f32>> {
+    let multi_speaker = synth
+        .model
+        .get_speakers()
+        .ok()
+        .flatten()
+        .is_some_and(|speakers| speakers.len() > 1);
+    if multi_speaker && let Some(e) = synth.model.set_speaker(speaker) {
+        return Err(e).context(format!("Failed to select speaker {}", speaker));
+    }
+
     let mut samples = Vec::new();
     let audio = synth
+        .speech
         .synthesize_parallel(text.to_string(), None)
         .context("Failed to synthesize speech")?;
 
@@ -179,9 +416,31 @@ fn synthesize_audio(synth: &PiperSpeechSynthesizer, text: &str) -> Result<Vec<f3
     Ok(samples)
 }
 
+/// Writes interleaved stereo samples to a 16-bit PCM WAV file (synchronous, blocking)
+fn write_wav(path: &Path, sample_rate: u32, samples: &[f32]) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: pan::CHANNELS,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .context(format!("Failed to create WAV file: {}", path.display()))?;
+
+    for &sample in samples {
+        let pcm = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer
+            .write_sample(pcm)
+            .context("Failed to write WAV sample")?;
+    }
+
+    writer.finalize().context("Failed to finalize WAV file")?;
+    Ok(())
+}
+
 /// Plays audio samples through the default audio device (synchronous, blocking)
 #[cfg(not(test))]
-fn play_audio(samples: Vec<f32>) -> Result<()> {
+fn play_audio(samples: Vec<f32>, sample_rate: u32) -> Result<()> {
     let mut stream_handle = rodio::OutputStreamBuilder::open_default_stream()
         .context("Failed to open default audio stream")?;
     // Disable noisy log messages related output stream being dropped after audio playback is done
@@ -189,7 +448,7 @@ fn play_audio(samples: Vec<f32>) -> Result<()> {
 
     let sink = rodio::Sink::connect_new(stream_handle.mixer());
 
-    let buf = SamplesBuffer::new(1, 22050, samples);
+    let buf = SamplesBuffer::new(pan::CHANNELS, sample_rate, samples);
     sink.append(buf);
     sink.sleep_until_end();
 
@@ -198,7 +457,7 @@ fn play_audio(samples: Vec<f32>) -> Result<()> {
 
 /// Mock audio playback for tests (no-op, returns immediately)
 #[cfg(test)]
-fn play_audio(_samples: Vec<f32>) -> Result<()> {
+fn play_audio(_samples: Vec<f32>, _sample_rate: u32) -> Result<()> {
     // Mock implementation - no actual audio playback in tests
     // This allows tests to run faster and in parallel without device contention
     Ok(())
@@ -229,6 +488,11 @@ mod tests {
     //! - Engine cloning for multi-task usage
     //! - Text handling (empty, special characters)
     //! - Audio precaching for faster playback
+    //! - WAV export with correct headers
+    //! - Pronunciation lexicon applied to cache keys
+    //! - Loudness normalization of cached clips
+    //! - Category chimes joined with speech and cached per style
+    //! - Stereo panning of cached clips
 
     use super::*;
     use std::sync::OnceLock;
@@ -328,11 +592,8 @@ mod tests {
         let mut handles = vec![];
         for i in 0..4 {
             let engine_clone = engine.clone();
-            let handle = tokio::spawn(async move {
-                engine_clone
-                    .announce(&format!("Message {}", i))
-                    .await
-            });
+            let handle =
+                tokio::spawn(async move { engine_clone.announce(&format!("Message {}", i)).await });
             handles.push(handle);
         }
 
@@ -401,9 +662,200 @@ mod tests {
 
         // Announce should succeed and use cached audio
         let result = engine.announce("test announcement").await;
+        assert!(result.is_ok(), "Announce with cached audio should succeed");
+    }
+
+    /// Test that render_to_wav() writes a WAV file with the model's sample rate
+    #[tokio::test]
+    async fn test_render_to_wav_writes_header() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+        engine
+            .precache(["charm break"])
+            .await
+            .expect("Precache should succeed");
+
+        let path = std::env::temp_dir().join("quarm_test_render.wav");
+        engine
+            .render_to_wav("charm break", &path)
+            .await
+            .expect("WAV export should succeed");
+
+        let reader = hound::WavReader::open(&path).expect("WAV file should be readable");
+        let spec = reader.spec();
+        assert_eq!(spec.sample_rate, engine.sample_rate());
+        assert_eq!(spec.channels, 2);
+        assert_eq!(spec.bits_per_sample, 16);
+        assert!(reader.len() > 0, "WAV file should contain samples");
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    /// Test that precache() keys the cache by lexicon-rewritten text
+    #[tokio::test]
+    async fn test_precache_applies_lexicon() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+        engine.set_lexicon(Lexicon {
+            entries: vec![quarm_config::Pronunciation::say("mez", "mezz")],
+        });
+
+        engine
+            .precache(["mez break"])
+            .await
+            .expect("Precache should succeed");
+
+        let style = AnnouncementStyle::default();
+        assert!(
+            engine
+                .audio_cache
+                .contains_key(&("mezz break".to_string(), style))
+        );
+        assert!(
+            !engine
+                .audio_cache
+                .contains_key(&("mez break".to_string(), style))
+        );
+
+        let result = engine.announce("mez break").await;
         assert!(
             result.is_ok(),
-            "Announce with cached audio should succeed"
+            "Announce with rewritten text should succeed"
         );
     }
+
+    /// Test that precache() stores normalized samples in the cache
+    #[tokio::test]
+    async fn test_precache_stores_normalized_samples() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+        let settings = AudioSettings {
+            normalize: true,
+            target_loudness_db: -18.0,
+            peak_ceiling_db: -1.0,
+            ..AudioSettings::default()
+        };
+        engine
+            .set_audio_settings(settings)
+            .await
+            .expect("Audio settings should apply");
+
+        engine
+            .precache(["root break"])
+            .await
+            .expect("Precache should succeed");
+
+        let samples = engine
+            .audio_cache
+            .get(&("root break".to_string(), AnnouncementStyle::default()))
+            .unwrap();
+        // Centered clips carry identical channels, so measure the left one
+        let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+        let measured = loudness::measure_loudness(&left, engine.sample_rate()).unwrap();
+        assert!((measured + 18.0).abs() < 1.0, "got {}", measured);
+    }
+
+    /// Test that a category chime is prepended and cached separately from plain speech
+    #[tokio::test]
+    async fn test_precache_styled_prepends_chime() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+
+        let plain = AnnouncementStyle::default();
+        let danger = AnnouncementStyle {
+            category: Some(TriggerCategory::Danger),
+            ..AnnouncementStyle::default()
+        };
+        engine
+            .precache_styled([
+                ("get out".to_string(), plain),
+                ("get out".to_string(), danger),
+            ])
+            .await
+            .expect("Precache should succeed");
+
+        let plain_len = engine.audio_cache[&("get out".to_string(), plain)].len();
+        let danger_len = engine.audio_cache[&("get out".to_string(), danger)].len();
+        // Cached clips are stereo, chimes are mono
+        let chime_len = engine.clip_renderer.chimes[&TriggerCategory::Danger].len() * 2;
+        assert!(chime_len > 0);
+        assert!(danger_len > plain_len + chime_len);
+
+        let result = engine.announce_styled("get out", danger).await;
+        assert!(result.is_ok(), "Styled announcement should succeed");
+    }
+
+    /// Test that a missing chime file is reported when applying audio settings
+    #[tokio::test]
+    async fn test_set_audio_settings_missing_chime_file() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+
+        let mut settings = AudioSettings::default();
+        settings.chimes.insert(
+            TriggerCategory::Tell,
+            quarm_config::Chime::File("./nonexistent/tell.wav".to_string()),
+        );
+        assert!(engine.set_audio_settings(settings).await.is_err());
+    }
+
+    /// Test that panned styles are cached as stereo with the far channel attenuated
+    #[tokio::test]
+    async fn test_precache_styled_pans_to_stereo() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+
+        let left = AnnouncementStyle {
+            pan: -100,
+            ..AnnouncementStyle::default()
+        };
+        engine
+            .precache_styled([("buff faded".to_string(), left)])
+            .await
+            .expect("Precache should succeed");
+
+        let samples = &engine.audio_cache[&("buff faded".to_string(), left)];
+        assert_eq!(samples.len() % 2, 0);
+        assert!(samples.iter().step_by(2).any(|s| *s != 0.0));
+        assert!(samples.iter().skip(1).step_by(2).all(|s| *s == 0.0));
+    }
+
+    /// Test that each voice of the same text is cached separately
+    #[tokio::test]
+    async fn test_precache_styled_caches_each_speaker() {
+        let mut engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+
+        let voices = [None, Some(SPEAKER_ID), Some(0)].map(|speaker| AnnouncementStyle {
+            speaker,
+            ..AnnouncementStyle::default()
+        });
+        engine
+            .precache_styled(voices.map(|style| ("charm break".to_string(), style)))
+            .await
+            .expect("Precache should succeed");
+
+        assert_eq!(engine.audio_cache.len(), 3);
+    }
+
+    /// Test that the bundled single-speaker voice synthesizes with the default style
+    #[tokio::test]
+    async fn test_default_style_with_bundled_voice() {
+        let engine = TtsEngine::new(get_test_model_path())
+            .await
+            .expect("Failed to initialize TtsEngine");
+
+        let synth = engine.synthesizer.lock().await;
+        let speaker = AnnouncementStyle::default().speaker.unwrap_or(SPEAKER_ID);
+        let samples = synthesize_audio(&synth, "charm break", speaker)
+            .expect("Synthesis should succeed with the default speaker");
+        assert!(!samples.is_empty());
+    }
 }
@@ -0,0 +1,153 @@
+//! Loudness measurement and normalization for synthesized clips
+//!
+//! Loudness is measured as a gated RMS level in dBFS, following the two-stage
+//! gating of ITU-R BS.1770 (without K-weighting) so leading/trailing silence and
+//! pauses between words don't drag short clips down.
+
+// Length of each measurement block
+const BLOCK_DURATION_SECS: f32 = 0.05;
+
+// Blocks quieter than this are treated as silence
+const ABSOLUTE_GATE_DB: f32 = -70.0;
+
+// Blocks more than this far below the ungated level are ignored
+const RELATIVE_GATE_DB: f32 = -10.0;
+
+// Time for the limiter to recover after reducing gain
+const LIMITER_RELEASE_SECS: f32 = 0.05;
+
+/// Measures the gated RMS loudness of a mono clip in dBFS
+/// Returns None for silent or empty clips
+pub fn measure_loudness(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    let block_len = ((sample_rate as f32 * BLOCK_DURATION_SECS) as usize).max(1);
+    let block_powers: Vec<f32> = samples
+        .chunks(block_len)
+        .map(|block| block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32)
+        .filter(|&power| power_to_db(power) > ABSOLUTE_GATE_DB)
+        .collect();
+
+    if block_powers.is_empty() {
+        return None;
+    }
+
+    let ungated = mean(&block_powers);
+    let threshold = power_to_db(ungated) + RELATIVE_GATE_DB;
+    let gated: Vec<f32> = block_powers
+        .into_iter()
+        .filter(|&power| power_to_db(power) > threshold)
+        .collect();
+
+    Some(power_to_db(mean(&gated)))
+}
+
+/// Scales a mono clip to the target loudness, then limits peaks to the ceiling
+/// Silent clips are left untouched
+pub fn normalize(samples: &mut [f32], sample_rate: u32, target_db: f32, ceiling_db: f32) {
+    let Some(loudness) = measure_loudness(samples, sample_rate) else {
+        return;
+    };
+
+    let gain = db_to_amplitude(target_db - loudness);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+
+    limit_peaks(samples, sample_rate, db_to_amplitude(ceiling_db));
+}
+
+/// Peak limiter with instant attack and exponential release
+/// Guarantees no sample exceeds `ceiling` while only briefly ducking around peaks
+fn limit_peaks(samples: &mut [f32], sample_rate: u32, ceiling: f32) {
+    let release = 1.0 - (-1.0 / (LIMITER_RELEASE_SECS * sample_rate as f32)).exp();
+    let mut gain = 1.0_f32;
+
+    for sample in samples.iter_mut() {
+        let magnitude = sample.abs();
+        let needed = if magnitude > ceiling {
+            ceiling / magnitude
+        } else {
+            1.0
+        };
+
+        gain = if needed < gain {
+            needed
+        } else {
+            (gain + (1.0 - gain) * release).min(needed)
+        };
+        *sample *= gain;
+    }
+}
+
+fn mean(values: &[f32]) -> f32 {
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+fn power_to_db(power: f32) -> f32 {
+    10.0 * power.max(f32::MIN_POSITIVE).log10()
+}
+
+fn db_to_amplitude(db: f32) -> f32 {
+    10.0_f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 22050;
+
+    fn sine(amplitude: f32, seconds: f32) -> Vec<f32> {
+        let len = (RATE as f32 * seconds) as usize;
+        (0..len)
+            .map(|i| amplitude * (i as f32 * 440.0 * std::f32::consts::TAU / RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_measure_loudness_of_sine() {
+        // A full-scale sine has an RMS of -3.01 dBFS
+        let loudness = measure_loudness(&sine(1.0, 1.0), RATE).unwrap();
+        assert!((loudness + 3.01).abs() < 0.1, "got {}", loudness);
+    }
+
+    #[test]
+    fn test_measure_loudness_ignores_silence() {
+        let mut samples = vec![0.0; RATE as usize];
+        samples.extend(sine(0.5, 0.5));
+        samples.extend(vec![0.0; RATE as usize]);
+
+        let with_silence = measure_loudness(&samples, RATE).unwrap();
+        let without = measure_loudness(&sine(0.5, 0.5), RATE).unwrap();
+        assert!((with_silence - without).abs() < 0.5);
+
+        assert!(measure_loudness(&[0.0; 1000], RATE).is_none());
+        assert!(measure_loudness(&[], RATE).is_none());
+    }
+
+    #[test]
+    fn test_normalize_matches_quiet_and_loud_clips() {
+        let mut quiet = sine(0.02, 0.3);
+        let mut loud = sine(0.6, 2.0);
+
+        normalize(&mut quiet, RATE, -20.0, -1.0);
+        normalize(&mut loud, RATE, -20.0, -1.0);
+
+        let quiet_db = measure_loudness(&quiet, RATE).unwrap();
+        let loud_db = measure_loudness(&loud, RATE).unwrap();
+        assert!((quiet_db + 20.0).abs() < 0.5, "got {}", quiet_db);
+        assert!((loud_db + 20.0).abs() < 0.5, "got {}", loud_db);
+    }
+
+    #[test]
+    fn test_normalize_limits_peaks() {
+        // A quiet clip with one spike would exceed the ceiling after gain is applied
+        let mut samples = sine(0.05, 1.0);
+        samples[RATE as usize / 2] = 1.0;
+
+        normalize(&mut samples, RATE, -6.0, -1.0);
+
+        let ceiling = db_to_amplitude(-1.0);
+        let peak = samples.iter().fold(0.0_f32, |max, s| max.max(s.abs()));
+        assert!(peak <= ceiling + 1e-6, "peak {} above ceiling", peak);
+    }
+}
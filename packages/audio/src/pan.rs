@@ -0,0 +1,48 @@
+//! Stereo placement of mono clips
+
+/// Number of channels in rendered clips
+pub const CHANNELS: u16 = 2;
+
+/// Converts a mono clip to interleaved stereo placed at `pan`
+///
+/// `pan` ranges from -1.0 (hard left) to 1.0 (hard right). A balance law is
+/// used: the near channel stays at full level and the far channel is attenuated,
+/// so centered clips sound exactly like the mono original on both speakers.
+pub fn pan_to_stereo(mono: &[f32], pan: f32) -> Vec<f32> {
+    let pan = pan.clamp(-1.0, 1.0);
+    let left_gain = (1.0 - pan).min(1.0);
+    let right_gain = (1.0 + pan).min(1.0);
+
+    let mut stereo = Vec::with_capacity(mono.len() * 2);
+    for &sample in mono {
+        stereo.push(sample * left_gain);
+        stereo.push(sample * right_gain);
+    }
+    stereo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_center_pan_duplicates_channels() {
+        let stereo = pan_to_stereo(&[0.5, -0.25], 0.0);
+        assert_eq!(stereo, vec![0.5, 0.5, -0.25, -0.25]);
+    }
+
+    #[test]
+    fn test_hard_pan_silences_far_channel() {
+        assert_eq!(pan_to_stereo(&[0.5], -1.0), vec![0.5, 0.0]);
+        assert_eq!(pan_to_stereo(&[0.5], 1.0), vec![0.0, 0.5]);
+        // Out of range values are clamped
+        assert_eq!(pan_to_stereo(&[0.5], 3.0), vec![0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_partial_pan_attenuates_far_channel() {
+        let stereo = pan_to_stereo(&[1.0], -0.5);
+        assert_eq!(stereo[0], 1.0);
+        assert!((stereo[1] - 0.5).abs() < 1e-6);
+    }
+}
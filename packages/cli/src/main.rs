@@ -0,0 +1,261 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use clap::{Parser, Subcommand};
+
+use quarm_audio::{AnnouncementStyle, TtsEngine};
+use quarm_config::{Config, DEFAULT_CONFIG_PATH, MessageConfig};
+use quarm_monitor::{
+    CancellationToken, LogMonitor, ReplayOptions, ReplaySpeed, announcement_variants, dry_run,
+    parse_replay_time,
+};
+
+/// Path to the bundled Piper voice model config, relative to the working directory
+const DEFAULT_MODEL_PATH: &str = "./resources/speakers/en_US-amy-medium.onnx.json";
+
+// Maximum length of each slug segment in exported file names
+const MAX_SLUG_LEN: usize = 48;
+
+/// Command-line tools for Quarm Announce
+#[derive(Parser, Debug)]
+#[command(name = "quarm_cli", version, about)]
+struct Cli {
+    /// Path to the configuration file
+    #[arg(long, global = true, default_value = DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
+
+    /// Path to the Piper voice model config (.onnx.json)
+    #[arg(long, global = true, default_value = DEFAULT_MODEL_PATH)]
+    model: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Render every configured announcement to a folder of WAV files
+    ExportWav {
+        /// Directory to write the WAV files into (created if missing)
+        #[arg(long, default_value = "./announcements")]
+        out: PathBuf,
+    },
+    /// Replay a recorded eqlog file through the configured triggers
+    Replay {
+        /// The eqlog_<Name>_<server>.txt file to replay
+        log: PathBuf,
+        /// Playback speed: a multiplier like 1x or 10x, or "instant"
+        #[arg(long, default_value = "1x")]
+        speed: ReplaySpeed,
+        /// Skip lines before this time (YYYY-MM-DD HH:MM:SS)
+        #[arg(long, value_parser = parse_replay_time)]
+        start: Option<NaiveDateTime>,
+        /// Stop at the first line after this time (YYYY-MM-DD HH:MM:SS)
+        #[arg(long, value_parser = parse_replay_time)]
+        end: Option<NaiveDateTime>,
+    },
+    /// Report which triggers would have fired over a recorded eqlog file, without audio
+    DryRun {
+        /// The eqlog_<Name>_<server>.txt file to analyze
+        log: PathBuf,
+        /// Print the report as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let config = Config::load(cli.config.to_string_lossy().as_ref())
+        .await
+        .context("Failed to load configuration")?;
+
+    match cli.command {
+        Command::ExportWav { out } => export_wav(&config, &cli.model, &out).await,
+        Command::Replay {
+            log,
+            speed,
+            start,
+            end,
+        } => {
+            let options = ReplayOptions { speed, start, end };
+            replay(config, &cli.model, &log, &options).await
+        }
+        Command::DryRun { log, json } => {
+            let report = dry_run(&config, &log)
+                .await
+                .context(format!("Failed to analyze: {}", log.display()))?;
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).context("Failed to serialize report")?
+                );
+            } else {
+                print!("{}", report);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Loads the voice model and applies the config's lexicon and audio settings
+async fn init_engine(config: &Config, model_path: &Path) -> Result<TtsEngine> {
+    let mut tts_engine = TtsEngine::new(model_path.to_string_lossy().as_ref())
+        .await
+        .context("Failed to initialize TTS engine")?;
+
+    tts_engine.set_lexicon(config.lexicon.clone());
+    tts_engine
+        .set_audio_settings(config.audio.clone())
+        .await
+        .context("Failed to apply audio settings")?;
+
+    Ok(tts_engine)
+}
+
+/// Plays a recorded log through the trigger engine, speaking its announcements
+/// Ctrl-C stops the replay along with its queued announcements and timers
+async fn replay(
+    config: Config,
+    model_path: &Path,
+    log: &Path,
+    options: &ReplayOptions,
+) -> Result<()> {
+    let mut tts_engine = init_engine(&config, model_path).await?;
+    tts_engine
+        .precache_styled(announcement_variants(&config))
+        .await
+        .context("Failed to pre-cache announcement audio")?;
+
+    let shutdown = CancellationToken::new();
+    let interrupted = shutdown.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            interrupted.cancel();
+        }
+    });
+
+    println!("Replaying {:?} ({:?})", log, options.speed);
+    let summary = LogMonitor::new(config, tts_engine)?
+        .replay(log, options, shutdown)
+        .await
+        .context(format!("Failed to replay: {}", log.display()))?;
+    println!(
+        "Replay {}: {} lines, {} matches",
+        if summary.stopped {
+            "stopped"
+        } else {
+            "finished"
+        },
+        summary.lines,
+        summary.matches
+    );
+    Ok(())
+}
+
+/// Renders each message's announcement to `<out>/<NN>-<pattern>--<announcement>.wav`
+async fn export_wav(config: &Config, model_path: &Path, out: &Path) -> Result<()> {
+    let mut tts_engine = init_engine(config, model_path).await?;
+
+    // Precache so each distinct announcement is synthesized only once
+    tts_engine
+        .precache_styled(config.messages.iter().map(|m| {
+            (
+                m.announcement().to_string(),
+                AnnouncementStyle::for_message(m),
+            )
+        }))
+        .await
+        .context("Failed to pre-cache announcement audio")?;
+
+    tokio::fs::create_dir_all(out).await.context(format!(
+        "Failed to create output directory: {}",
+        out.display()
+    ))?;
+
+    for (index, message) in config.messages.iter().enumerate() {
+        let path = out.join(wav_file_name(index, message));
+        tts_engine
+            .render_styled_to_wav(
+                message.announcement(),
+                AnnouncementStyle::for_message(message),
+                &path,
+            )
+            .await
+            .context(format!("Failed to render: {}", path.display()))?;
+        println!("Wrote {}", path.display());
+    }
+
+    println!(
+        "Exported {} announcements at {} Hz",
+        config.messages.len(),
+        tts_engine.sample_rate()
+    );
+    Ok(())
+}
+
+/// Builds a file name from the trigger's position, pattern and announcement
+/// The index prefix keeps names unique when several triggers share a pattern
+fn wav_file_name(index: usize, message: &MessageConfig) -> String {
+    format!(
+        "{:02}-{}--{}.wav",
+        index + 1,
+        slugify(message.pattern()),
+        slugify(message.announcement())
+    )
+}
+
+/// Lowercases text and collapses anything that isn't ASCII alphanumeric into single dashes
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "blank".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quarm_config::TriggerOptions;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(
+            slugify("Root spell has worn off"),
+            "root-spell-has-worn-off"
+        );
+        assert_eq!(slugify("  Cazic-Thule's  wrath!"), "cazic-thule-s-wrath");
+        assert_eq!(slugify("!!!"), "blank");
+        assert!(slugify(&"a".repeat(100)).len() <= MAX_SLUG_LEN);
+    }
+
+    #[test]
+    fn test_wav_file_name() {
+        let message = MessageConfig::TimedDelay {
+            pattern: "flesh begins to liquefy".to_string(),
+            announcement: "get out".to_string(),
+            timer_delay_in_seconds: 22,
+            options: TriggerOptions::default(),
+        };
+        assert_eq!(
+            wav_file_name(4, &message),
+            "05-flesh-begins-to-liquefy--get-out.wav"
+        );
+    }
+}
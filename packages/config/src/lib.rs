@@ -1,23 +1,171 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 /// Path to the default configuration file
 pub static DEFAULT_CONFIG_PATH: &str = "./config.json";
 
+/// Category of a trigger, used to pick the chime played before its announcement
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerCategory {
+    /// Something needs attention right now (breaks, AE, get out)
+    Danger,
+    /// Routine status (buffs, timers)
+    Info,
+    /// Someone is talking to you
+    Tell,
+}
+
+/// Types of classified game events a trigger can target
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// `Soandso tells you, '...'`
+    Tell,
+    /// Say, group, guild, raid, ooc, shout and auction chat
+    Chat,
+    /// `X has been slain by Y!`, `You have slain X!`
+    Slain,
+    /// `You have entered X.`
+    ZoneEntered,
+    /// `Your X spell has worn off.`
+    SpellWornOff,
+    /// `Your target resisted the X spell.`, `You resist the X spell!`
+    SpellResisted,
+    /// `X hits Y for N points of damage.`
+    Damage,
+}
+
+/// Public chat channels recognized by the event classifier
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatChannel {
+    Say,
+    Group,
+    Guild,
+    Raid,
+    Ooc,
+    Shout,
+    Auction,
+}
+
+/// Conditions on a classified game event; every field that is set must match
+/// Names are compared case-insensitively
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EventCondition {
+    pub kind: EventKind,
+    /// Who spoke (tell/chat) or who attacked/killed (damage/slain)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Chat channel (chat only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ChatChannel>,
+    /// Zone name (zone entered only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Spell name (worn off/resisted only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spell: Option<String>,
+    /// Who was hit or slain (damage/slain only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Substring of the spoken text (tell/chat only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_contains: Option<String>,
+    /// Minimum damage amount (damage only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<u32>,
+}
+
+impl EventCondition {
+    /// Creates a condition matching every event of a kind
+    pub fn kind(kind: EventKind) -> Self {
+        Self {
+            kind,
+            speaker: None,
+            channel: None,
+            zone: None,
+            spell: None,
+            target: None,
+            text_contains: None,
+            min_amount: None,
+        }
+    }
+}
+
+/// Zones a trigger is limited to, compared case-insensitively against the current zone
+/// (e.g. `The Plane of Fear`, as written in "You have entered The Plane of Fear.")
+#[derive(Default, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ZoneFilter {
+    /// Zones the trigger fires in; any zone when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Zones the trigger never fires in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl ZoneFilter {
+    /// Checks whether a trigger may fire in the given zone
+    /// An unknown zone only passes when there is no include list
+    pub fn allows(&self, zone: Option<&str>) -> bool {
+        let listed = |zones: &[String]| {
+            zone.is_some_and(|zone| zones.iter().any(|z| z.eq_ignore_ascii_case(zone)))
+        };
+        (self.include.is_empty() || listed(&self.include)) && !listed(&self.exclude)
+    }
+}
+
+/// Options shared by every message type
+#[derive(Default, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TriggerOptions {
+    /// Name character profiles use to pick this trigger; its pattern when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Classified event the line must match, in addition to the pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<EventCondition>,
+    /// Zones the trigger is limited to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zones: Option<ZoneFilter>,
+    /// Characters the trigger fires for (case-insensitive); every character when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub characters: Vec<String>,
+    /// Category selecting the chime played right before the announcement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<TriggerCategory>,
+    /// Stereo position from -1.0 (left) to 1.0 (right); centered when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pan: Option<f32>,
+    /// Treat the pattern as a regular expression rather than plain text
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub regex: bool,
+}
+
 /// Message configuration variants
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MessageConfig {
     /// Immediate announcement when pattern matches
+    /// An empty pattern matches every line (useful with an `event` condition)
     Simple {
+        #[serde(default)]
         pattern: String,
         announcement: String,
+        #[serde(flatten)]
+        options: TriggerOptions,
     },
     /// Delayed announcement triggered after a timer
     TimedDelay {
+        #[serde(default)]
         pattern: String,
         announcement: String,
         timer_delay_in_seconds: u64,
+        #[serde(flatten)]
+        options: TriggerOptions,
     },
 }
 
@@ -37,13 +185,423 @@ impl MessageConfig {
             MessageConfig::TimedDelay { announcement, .. } => announcement,
         }
     }
+
+    /// Get the options shared by every message type
+    pub fn options(&self) -> &TriggerOptions {
+        match self {
+            MessageConfig::Simple { options, .. } => options,
+            MessageConfig::TimedDelay { options, .. } => options,
+        }
+    }
+
+    /// Get the id character profiles refer to this trigger by: its `id` option, or else its
+    /// pattern
+    pub fn id(&self) -> &str {
+        self.options().id.as_deref().unwrap_or(self.pattern())
+    }
+
+    /// Checks whether the pattern is a regular expression rather than plain text
+    pub fn is_regex(&self) -> bool {
+        self.options().regex
+    }
+
+    /// Get the category for this message config, if any
+    pub fn category(&self) -> Option<TriggerCategory> {
+        self.options().category
+    }
+
+    /// Get the event condition for this message config, if any
+    pub fn event(&self) -> Option<&EventCondition> {
+        self.options().event.as_ref()
+    }
+
+    /// Checks whether this message may fire in the given zone
+    pub fn allowed_in_zone(&self, zone: Option<&str>) -> bool {
+        self.options()
+            .zones
+            .as_ref()
+            .is_none_or(|zones| zones.allows(zone))
+    }
+
+    /// Checks whether this message may fire for the given character
+    pub fn allowed_for_character(&self, character: &str) -> bool {
+        let characters = &self.options().characters;
+        characters.is_empty() || characters.iter().any(|c| c.eq_ignore_ascii_case(character))
+    }
+
+    /// Get the stereo position for this message config (0.0 is centered)
+    pub fn pan(&self) -> f32 {
+        self.options().pan.unwrap_or(0.0)
+    }
+}
+
+/// A single pronunciation override applied to announcement text before synthesis
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Pronunciation {
+    /// Word or phrase to replace, matched as whole words and case-insensitively
+    pub word: String,
+    /// Text to speak instead of `word`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub say: Option<String>,
+    /// espeak-ng phoneme mnemonics to speak instead of `word` (takes precedence over `say`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phonemes: Option<String>,
+}
+
+impl Pronunciation {
+    /// Creates a plain text replacement
+    pub fn say(word: &str, say: &str) -> Self {
+        Self {
+            word: word.to_string(),
+            say: Some(say.to_string()),
+            phonemes: None,
+        }
+    }
+
+    /// Returns the text that replaces `word` in the synthesizer input
+    /// Phonemes are wrapped in `[[...]]`, espeak-ng's inline phoneme syntax
+    pub fn replacement(&self) -> Cow<'_, str> {
+        match &self.phonemes {
+            Some(phonemes) => Cow::Owned(format!("[[{}]]", phonemes)),
+            None => Cow::Borrowed(self.say.as_deref().unwrap_or(&self.word)),
+        }
+    }
+}
+
+/// Pronunciation dictionary for names and jargon that Piper mispronounces
+#[derive(Default, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct Lexicon {
+    pub entries: Vec<Pronunciation>,
+}
+
+impl Lexicon {
+    /// Returns the lexicon shipped with the app for common EverQuest names and jargon
+    pub fn eq_default() -> Self {
+        let entries = [
+            ("Vex Thal", "vex thawl"),
+            ("Cazic-Thule", "kazick thool"),
+            ("Cazic Thule", "kazick thool"),
+            ("Innoruuk", "in oh rook"),
+            ("Nagafen", "nahgah fen"),
+            ("Sebilis", "seh billis"),
+            ("Kael Drakkel", "kale drackel"),
+            ("Qeynos", "kay nose"),
+            ("Erudin", "air oo din"),
+            ("Tunare", "too nar ay"),
+            ("Kunark", "koo nark"),
+            ("Velious", "vell ee us"),
+            ("Luclin", "loo klin"),
+            ("Quarm", "kworm"),
+            ("mez", "mezz"),
+            ("mezzed", "mezzd"),
+            ("AE", "A E"),
+            ("PBAE", "P B A E"),
+            ("FD", "feign death"),
+            ("CH", "complete heal"),
+            ("OOM", "out of mana"),
+            ("LoM", "low on mana"),
+            ("inc", "incoming"),
+        ];
+        Self {
+            entries: entries
+                .into_iter()
+                .map(|(word, say)| Pronunciation::say(word, say))
+                .collect(),
+        }
+    }
+
+    /// Rewrites text by replacing every whole-word occurrence of a lexicon entry
+    /// Longer entries win when several match at the same position
+    pub fn apply(&self, text: &str) -> String {
+        if self.entries.is_empty() {
+            return text.to_string();
+        }
+
+        // Lowercase copies keep byte offsets aligned with the original (ASCII-only lowering)
+        let lower_text = text.to_ascii_lowercase();
+        let mut entries: Vec<(String, &Pronunciation)> = self
+            .entries
+            .iter()
+            .filter(|entry| !entry.word.is_empty())
+            .map(|entry| (entry.word.to_ascii_lowercase(), entry))
+            .collect();
+        entries.sort_by_key(|(word, _)| std::cmp::Reverse(word.len()));
+
+        let mut output = String::with_capacity(text.len());
+        let mut pos = 0;
+        while pos < text.len() {
+            let at_word_start = !text[..pos].chars().next_back().is_some_and(is_word_char);
+            let matched = at_word_start
+                .then(|| {
+                    entries.iter().find(|(word, _)| {
+                        lower_text[pos..].starts_with(word.as_str())
+                            && !text[pos + word.len()..]
+                                .chars()
+                                .next()
+                                .is_some_and(is_word_char)
+                    })
+                })
+                .flatten();
+
+            match matched {
+                Some((word, entry)) => {
+                    output.push_str(&entry.replacement());
+                    pos += word.len();
+                }
+                None => {
+                    let c = text[pos..].chars().next().unwrap();
+                    output.push(c);
+                    pos += c.len_utf8();
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Characters that continue a word for whole-word lexicon matching
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+/// A single sine tone in a generated chime
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Tone {
+    pub frequency_hz: f32,
+    pub duration_ms: u64,
+}
+
+impl Tone {
+    pub fn new(frequency_hz: f32, duration_ms: u64) -> Self {
+        Self {
+            frequency_hz,
+            duration_ms,
+        }
+    }
+}
+
+/// Short sound played right before an announcement
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Chime {
+    /// Sequence of generated sine tones
+    Tones(Vec<Tone>),
+    /// Path to a WAV file
+    File(String),
+}
+
+/// Audio processing applied to every synthesized clip
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Normalize each clip to `target_loudness_db` before caching and playback
+    pub normalize: bool,
+    /// Target loudness as gated RMS level in dBFS
+    pub target_loudness_db: f32,
+    /// Maximum sample peak in dBFS after normalization
+    pub peak_ceiling_db: f32,
+    /// Chime played before announcements of each trigger category
+    pub chimes: BTreeMap<TriggerCategory, Chime>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            normalize: true,
+            target_loudness_db: -20.0,
+            peak_ceiling_db: -1.0,
+            chimes: BTreeMap::from([
+                (
+                    TriggerCategory::Danger,
+                    Chime::Tones(vec![Tone::new(988.0, 70), Tone::new(988.0, 70)]),
+                ),
+                (
+                    TriggerCategory::Info,
+                    Chime::Tones(vec![Tone::new(660.0, 90)]),
+                ),
+                (
+                    TriggerCategory::Tell,
+                    Chime::Tones(vec![Tone::new(523.0, 70), Tone::new(784.0, 90)]),
+                ),
+            ]),
+        }
+    }
+}
+
+/// Log monitor behavior
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct MonitorSettings {
+    /// Join immediate announcements matched within `combine_window_ms` into one phrase
+    pub combine_announcements: bool,
+    /// How long to collect announcements before speaking the combined phrase
+    pub combine_window_ms: u64,
+    /// Tail every character's log modified within `active_log_window_secs`,
+    /// instead of only the most recently modified one
+    pub all_characters: bool,
+    /// How recently a log must have been written to be tailed in `all_characters` mode
+    pub active_log_window_secs: u64,
+    /// Start each announcement with the name of the character whose log matched
+    pub prefix_character_name: bool,
+    /// File pending timers are saved to, so they survive a restart; not saved when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timer_state_file: Option<String>,
+    /// What to do with saved timers that ran out while monitoring was stopped
+    pub late_timers: LateTimerPolicy,
+    /// When monitoring stops, leave pending timers in the state file for the next start
+    /// instead of cancelling them; either way they don't fire while stopped
+    pub keep_timers_on_stop: bool,
+    /// Lines read this many seconds after they were written are stale, e.g. after a disk
+    /// stall or the computer sleeping; `null` treats every line as fresh
+    pub max_line_lag_secs: Option<u64>,
+    /// What to do with matches on stale lines
+    pub stale_lines: StaleLinePolicy,
+    /// Where to start reading the logs found when monitoring starts
+    pub start_position: StartPosition,
+    /// File the position reached in each log is saved to when monitoring stops, for
+    /// `start_position` `resume`; not saved when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmark_file: Option<String>,
+    /// Encoding the game writes logs in, e.g. "windows-1252" or "utf-8"
+    /// Bytes that aren't valid in it are replaced rather than stopping monitoring
+    pub log_encoding: String,
+}
+
+/// What to do with a match on a line read long after it was written
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleLinePolicy {
+    /// Don't announce it or start its timer
+    #[default]
+    Suppress,
+    /// Don't announce it, but run its timer for whatever is left of the delay, if anything
+    Shorten,
+}
+
+/// Where monitoring starts reading a log
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(tag = "from", rename_all = "snake_case")]
+pub enum StartPosition {
+    /// Only lines written from now on
+    #[default]
+    End,
+    /// Where the last session stopped reading, from `bookmark_file`; the end for a log
+    /// without a bookmark
+    Resume,
+    /// The last `lines` lines
+    LastLines { lines: usize },
+    /// Lines written in the last `seconds` seconds
+    LastSeconds { seconds: u64 },
+}
+
+/// What to do with a saved timer whose deadline passed while monitoring was stopped
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LateTimerPolicy {
+    /// Forget it; the moment has passed
+    #[default]
+    Drop,
+    /// Announce it as soon as monitoring starts
+    Announce,
+}
+
+impl Default for MonitorSettings {
+    fn default() -> Self {
+        Self {
+            combine_announcements: false,
+            combine_window_ms: 300,
+            all_characters: false,
+            active_log_window_secs: 600,
+            prefix_character_name: false,
+            timer_state_file: None,
+            late_timers: LateTimerPolicy::Drop,
+            keep_timers_on_stop: false,
+            max_line_lag_secs: Some(30),
+            stale_lines: StaleLinePolicy::Suppress,
+            start_position: StartPosition::End,
+            bookmark_file: None,
+            log_encoding: "windows-1252".to_string(),
+        }
+    }
+}
+
+/// Per-character settings for multiboxing, applied to matches from that character's log
+#[derive(Default, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CharacterProfile {
+    /// Ids of the triggers enabled for this character; every trigger when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triggers: Option<Vec<String>>,
+    /// Piper speaker id of the voice used for this character's announcements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<i64>,
+    /// Spoken before this character's announcements, e.g. "enchanter"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+impl CharacterProfile {
+    /// Checks whether a trigger is enabled for this character
+    pub fn enables(&self, message: &MessageConfig) -> bool {
+        self.triggers
+            .as_ref()
+            .is_none_or(|triggers| triggers.iter().any(|t| t == message.id()))
+    }
+}
+
+/// Character profiles keyed by the name in `eqlog_<Name>_<server>.txt`
+#[derive(Default, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct Profiles {
+    pub by_character: BTreeMap<String, CharacterProfile>,
+}
+
+impl Profiles {
+    /// Returns the profile of a character, matching the name case-insensitively
+    pub fn get(&self, character: &str) -> Option<&CharacterProfile> {
+        self.by_character
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(character))
+            .map(|(_, profile)| profile)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_character.is_empty()
+    }
 }
 
 /// Application configuration
-#[derive(Default, Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Config {
     pub game_directory: String,
     pub messages: Vec<MessageConfig>,
+    /// Pronunciation overrides; the EverQuest default lexicon is used when omitted
+    #[serde(default = "Lexicon::eq_default")]
+    pub lexicon: Lexicon,
+    /// Loudness normalization settings
+    #[serde(default)]
+    pub audio: AudioSettings,
+    /// Log monitor settings
+    #[serde(default)]
+    pub monitor: MonitorSettings,
+    /// Per-character trigger sets, voices and prefixes
+    #[serde(default, skip_serializing_if = "Profiles::is_empty")]
+    pub profiles: Profiles,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            game_directory: String::new(),
+            messages: Vec::new(),
+            // Same as a config file that leaves the lexicon out
+            lexicon: Lexicon::eq_default(),
+            audio: AudioSettings::default(),
+            monitor: MonitorSettings::default(),
+            profiles: Profiles::default(),
+        }
+    }
 }
 
 impl Config {
@@ -61,8 +619,8 @@ impl Config {
 
     /// Saves configuration to the specified path
     pub async fn save(&self, path: &str) -> Result<()> {
-        let json = serde_json::to_string_pretty(self)
-            .context("Failed to serialize config to JSON")?;
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize config to JSON")?;
 
         tokio::fs::write(path, json)
             .await
@@ -81,6 +639,7 @@ mod tests {
         let simple = MessageConfig::Simple {
             pattern: "test pattern".to_string(),
             announcement: "test announcement".to_string(),
+            options: TriggerOptions::default(),
         };
         assert_eq!(simple.pattern(), "test pattern");
 
@@ -88,6 +647,7 @@ mod tests {
             pattern: "timed pattern".to_string(),
             announcement: "timed announcement".to_string(),
             timer_delay_in_seconds: 30,
+            options: TriggerOptions::default(),
         };
         assert_eq!(timed.pattern(), "timed pattern");
     }
@@ -97,6 +657,7 @@ mod tests {
         let simple = MessageConfig::Simple {
             pattern: "test pattern".to_string(),
             announcement: "test announcement".to_string(),
+            options: TriggerOptions::default(),
         };
         assert_eq!(simple.announcement(), "test announcement");
 
@@ -104,6 +665,7 @@ mod tests {
             pattern: "timed pattern".to_string(),
             announcement: "timed announcement".to_string(),
             timer_delay_in_seconds: 30,
+            options: TriggerOptions::default(),
         };
         assert_eq!(timed.announcement(), "timed announcement");
     }
@@ -113,5 +675,287 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.game_directory, "");
         assert_eq!(config.messages.len(), 0);
+        assert_eq!(config.lexicon, Lexicon::eq_default());
+    }
+
+    #[test]
+    fn test_timer_persistence_settings() {
+        let settings = MonitorSettings::default();
+        assert_eq!(settings.timer_state_file, None);
+        assert_eq!(settings.late_timers, LateTimerPolicy::Drop);
+        assert!(!settings.keep_timers_on_stop);
+        assert!(
+            serde_json::to_value(&settings)
+                .unwrap()
+                .get("timer_state_file")
+                .is_none()
+        );
+
+        let settings: MonitorSettings = serde_json::from_str(
+            r#"{"timer_state_file": "timers.json", "late_timers": "announce",
+                "keep_timers_on_stop": true}"#,
+        )
+        .unwrap();
+        assert_eq!(settings.timer_state_file.as_deref(), Some("timers.json"));
+        assert_eq!(settings.late_timers, LateTimerPolicy::Announce);
+        assert!(settings.keep_timers_on_stop);
+    }
+
+    #[test]
+    fn test_stale_line_settings() {
+        let settings = MonitorSettings::default();
+        assert_eq!(settings.max_line_lag_secs, Some(30));
+        assert_eq!(settings.stale_lines, StaleLinePolicy::Suppress);
+
+        let settings: MonitorSettings =
+            serde_json::from_str(r#"{"max_line_lag_secs": null, "stale_lines": "shorten"}"#)
+                .unwrap();
+        assert_eq!(settings.max_line_lag_secs, None);
+        assert_eq!(settings.stale_lines, StaleLinePolicy::Shorten);
+    }
+
+    #[test]
+    fn test_start_position_settings() {
+        let settings = MonitorSettings::default();
+        assert_eq!(settings.start_position, StartPosition::End);
+        assert!(
+            serde_json::to_value(&settings)
+                .unwrap()
+                .get("bookmark_file")
+                .is_none()
+        );
+
+        let settings: MonitorSettings = serde_json::from_str(
+            r#"{"start_position": {"from": "last_seconds", "seconds": 120},
+                "bookmark_file": "bookmarks.json"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.start_position,
+            StartPosition::LastSeconds { seconds: 120 }
+        );
+        assert_eq!(settings.bookmark_file.as_deref(), Some("bookmarks.json"));
+
+        let position: StartPosition = serde_json::from_str(r#"{"from": "resume"}"#).unwrap();
+        assert_eq!(position, StartPosition::Resume);
+        assert_eq!(
+            serde_json::to_value(StartPosition::LastLines { lines: 50 }).unwrap(),
+            serde_json::json!({"from": "last_lines", "lines": 50})
+        );
+    }
+
+    #[test]
+    fn test_lexicon_whole_word_replacement() {
+        let lexicon = Lexicon {
+            entries: vec![
+                Pronunciation::say("mez", "mezz"),
+                Pronunciation::say("Vex Thal", "vex thawl"),
+            ],
+        };
+
+        assert_eq!(lexicon.apply("mez the adds"), "mezz the adds");
+        assert_eq!(lexicon.apply("MEZ broke!"), "mezz broke!");
+        assert_eq!(lexicon.apply("entering vex thal"), "entering vex thawl");
+        // Partial words are left alone
+        assert_eq!(lexicon.apply("mezmerize"), "mezmerize");
+        assert_eq!(lexicon.apply("Vex Thalia"), "Vex Thalia");
+    }
+
+    #[test]
+    fn test_lexicon_prefers_longest_and_phonemes() {
+        let lexicon = Lexicon {
+            entries: vec![
+                Pronunciation::say("AE", "A E"),
+                Pronunciation::say("PBAE", "P B A E"),
+                Pronunciation {
+                    word: "Cazic-Thule".to_string(),
+                    say: Some("kazick thool".to_string()),
+                    phonemes: Some("k'azIk T'u:l".to_string()),
+                },
+            ],
+        };
+
+        assert_eq!(lexicon.apply("PBAE now"), "P B A E now");
+        assert_eq!(lexicon.apply("AE incoming"), "A E incoming");
+        assert_eq!(
+            lexicon.apply("Cazic-Thule's avatar"),
+            "[[k'azIk T'u:l]]'s avatar"
+        );
+    }
+
+    #[test]
+    fn test_config_lexicon_defaults_to_eq_lexicon() {
+        let config: Config =
+            serde_json::from_str(r#"{"game_directory": "", "messages": []}"#).unwrap();
+        assert_eq!(config.lexicon, Lexicon::eq_default());
+
+        let config: Config =
+            serde_json::from_str(r#"{"game_directory": "", "messages": [], "lexicon": []}"#)
+                .unwrap();
+        assert!(config.lexicon.entries.is_empty());
+    }
+
+    #[test]
+    fn test_message_config_options_round_trip() {
+        let json = r#"{
+            "type": "timed_delay",
+            "pattern": "flesh begins to liquefy",
+            "announcement": "get out",
+            "timer_delay_in_seconds": 22,
+            "category": "danger",
+            "pan": -0.5
+        }"#;
+        let message: MessageConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(message.category(), Some(TriggerCategory::Danger));
+        assert_eq!(message.pan(), -0.5);
+
+        let value = serde_json::to_value(&message).unwrap();
+        assert_eq!(value["category"], "danger");
+        assert_eq!(value["timer_delay_in_seconds"], 22);
+
+        // Category is optional and omitted when unset
+        let message: MessageConfig =
+            serde_json::from_str(r#"{"type": "simple", "pattern": "p", "announcement": "a"}"#)
+                .unwrap();
+        assert_eq!(message.category(), None);
+        assert_eq!(message.pan(), 0.0);
+        let value = serde_json::to_value(&message).unwrap();
+        assert!(value.get("category").is_none());
+        assert!(value.get("pan").is_none());
+    }
+
+    #[test]
+    fn test_regex_trigger() {
+        let json = r#"{
+            "type": "simple",
+            "pattern": "^(\\w+) tells you, 'invite",
+            "announcement": "invite request",
+            "regex": true
+        }"#;
+        let message: MessageConfig = serde_json::from_str(json).unwrap();
+        assert!(message.is_regex());
+        assert_eq!(message.pattern(), r"^(\w+) tells you, 'invite");
+
+        // Plain text unless set, and omitted when unset
+        let message: MessageConfig =
+            serde_json::from_str(r#"{"type": "simple", "pattern": "p", "announcement": "a"}"#)
+                .unwrap();
+        assert!(!message.is_regex());
+        assert!(!serde_json::to_string(&message).unwrap().contains("regex"));
+    }
+
+    #[test]
+    fn test_event_trigger_without_pattern() {
+        let json = r#"{
+            "type": "simple",
+            "announcement": "tell from the guild leader",
+            "event": { "kind": "tell", "speaker": "Soandso" }
+        }"#;
+        let message: MessageConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(message.pattern(), "");
+
+        let event = message.event().unwrap();
+        assert_eq!(event.kind, EventKind::Tell);
+        assert_eq!(event.speaker.as_deref(), Some("Soandso"));
+        assert_eq!(event.channel, None);
+    }
+
+    #[test]
+    fn test_zone_filter() {
+        let json = r#"{
+            "type": "simple",
+            "pattern": "flesh begins to liquefy",
+            "announcement": "go back in",
+            "zones": { "include": ["The Plane of Fear"] }
+        }"#;
+        let message: MessageConfig = serde_json::from_str(json).unwrap();
+        assert!(message.allowed_in_zone(Some("the plane of fear")));
+        assert!(!message.allowed_in_zone(Some("The Plane of Hate")));
+        assert!(!message.allowed_in_zone(None));
+
+        let exclude = ZoneFilter {
+            include: Vec::new(),
+            exclude: vec!["The Plane of Hate".to_string()],
+        };
+        assert!(exclude.allows(Some("The Plane of Fear")));
+        assert!(!exclude.allows(Some("The Plane of Hate")));
+        assert!(exclude.allows(None));
+
+        // Messages without a filter fire everywhere
+        let message = MessageConfig::Simple {
+            pattern: "charm spell has worn off".to_string(),
+            announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
+        };
+        assert!(message.allowed_in_zone(None));
+        assert!(message.allowed_in_zone(Some("The Plane of Hate")));
+    }
+
+    #[test]
+    fn test_character_scoped_trigger() {
+        let json = r#"{
+            "type": "simple",
+            "pattern": "Your Mesmerization spell has worn off",
+            "announcement": "mez break",
+            "characters": ["Soandso"]
+        }"#;
+        let message: MessageConfig = serde_json::from_str(json).unwrap();
+        assert!(message.allowed_for_character("soandso"));
+        assert!(!message.allowed_for_character("Otherguy"));
+
+        // Round trip keeps the list, and omits it when empty
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert!(serialized.contains(r#""characters":["Soandso"]"#));
+        let message = MessageConfig::Simple {
+            pattern: "charm spell has worn off".to_string(),
+            announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
+        };
+        assert!(
+            !serde_json::to_string(&message)
+                .unwrap()
+                .contains("characters")
+        );
+        assert!(message.allowed_for_character("Otherguy"));
+    }
+
+    #[test]
+    fn test_character_profiles() {
+        let json = r#"{
+            "game_directory": "/eq",
+            "messages": [
+                { "type": "simple", "pattern": "charm spell has worn off", "announcement": "charm break" },
+                { "type": "simple", "pattern": "Root spell has worn off", "announcement": "root break" },
+                { "type": "simple", "pattern": "You feel less charming", "announcement": "charm break", "id": "charm fade" }
+            ],
+            "profiles": {
+                "Soandso": { "triggers": ["charm spell has worn off"], "speaker": 2, "prefix": "enchanter" },
+                "Otherenc": { "triggers": ["charm fade"] },
+                "Otherguy": {}
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        let profile = config.profiles.get("soandso").unwrap();
+        assert_eq!(profile.speaker, Some(2));
+        assert_eq!(profile.prefix.as_deref(), Some("enchanter"));
+        assert!(profile.enables(&config.messages[0]));
+        assert!(!profile.enables(&config.messages[1]));
+        assert!(!profile.enables(&config.messages[2]));
+
+        // Triggers sharing an announcement are still told apart
+        let profile = config.profiles.get("Otherenc").unwrap();
+        assert_eq!(config.messages[2].id(), "charm fade");
+        assert!(!profile.enables(&config.messages[0]));
+        assert!(profile.enables(&config.messages[2]));
+
+        // A profile without a trigger list enables everything
+        let profile = config.profiles.get("Otherguy").unwrap();
+        assert!(config.messages.iter().all(|m| profile.enables(m)));
+        assert!(config.profiles.get("Nobody").is_none());
+
+        // Configs without profiles don't gain an empty "profiles" key
+        let config = Config::default();
+        assert!(!serde_json::to_string(&config).unwrap().contains("profiles"));
     }
 }
@@ -0,0 +1,125 @@
+//! Compares matching every trigger with one compiled `PatternSet` against checking each
+//! trigger's pattern in turn, for a large imported trigger pack over a spammy raid log
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use regex::Regex;
+
+use quarm_config::{MessageConfig, TriggerOptions};
+use quarm_monitor::PatternSet;
+
+const TRIGGERS: usize = 1_000;
+const LINES: usize = 100_000;
+/// One trigger in this many is a regex
+const REGEX_EVERY: usize = 20;
+
+fn triggers() -> Vec<MessageConfig> {
+    (0..TRIGGERS)
+        .map(|i| {
+            let regex = i % REGEX_EVERY == 0;
+            let pattern = match i % 4 {
+                _ if regex => format!(r"^Mob{:03} hits YOU for \d{{3,}} points", i),
+                0 => format!("Your Spell{:03} spell has worn off", i),
+                1 => format!("Mob{:03} has been slain by", i),
+                2 => format!("Mob{:03} begins to cast a spell.", i),
+                _ => format!("tells the raid, 'Phrase{:03}", i),
+            };
+            MessageConfig::Simple {
+                pattern,
+                announcement: format!("trigger {}", i),
+                options: TriggerOptions {
+                    regex,
+                    ..TriggerOptions::default()
+                },
+            }
+        })
+        .collect()
+}
+
+/// Mostly melee spam that matches nothing, with an occasional line for some trigger
+fn log_lines() -> Vec<String> {
+    (0..LINES)
+        .map(|i| {
+            let mob = (i * 7) % (TRIGGERS * 2);
+            match i % 10 {
+                0 => format!("Mob{:03} hits YOU for {} points of damage.", mob, i % 900),
+                1 => format!("Your Spell{:03} spell has worn off.", mob),
+                2 => format!("Soandso tells the raid, 'Phrase{:03} now'", mob),
+                3 => format!("Mob{:03} begins to cast a spell.", mob),
+                _ => format!(
+                    "Soandso hits Mob{:03} for {} points of damage.",
+                    mob,
+                    i % 300
+                ),
+            }
+        })
+        .collect()
+}
+
+/// The matching done before patterns were compiled: each trigger checked in turn
+struct LinearScan {
+    patterns: Vec<(String, Option<Regex>)>,
+}
+
+impl LinearScan {
+    fn new(messages: &[MessageConfig]) -> Self {
+        let patterns = messages
+            .iter()
+            .map(|message| {
+                let regex = message
+                    .is_regex()
+                    .then(|| Regex::new(message.pattern()).unwrap());
+                (message.pattern().to_string(), regex)
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    fn matches(&self, line: &str) -> Vec<usize> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, (pattern, regex))| match regex {
+                Some(regex) => regex.is_match(line),
+                None => line.contains(pattern.as_str()),
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+fn bench_matching(c: &mut Criterion) {
+    let messages = triggers();
+    let lines = log_lines();
+    let linear = LinearScan::new(&messages);
+    let patterns = PatternSet::new(&messages).unwrap();
+
+    // Both must find the same triggers for the comparison to mean anything
+    for line in lines.iter().step_by(97) {
+        assert_eq!(patterns.matches(line), linear.matches(line));
+    }
+
+    let mut group = c.benchmark_group("1k triggers x 100k lines");
+    group.sample_size(10);
+    group.bench_function("linear scan", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| linear.matches(black_box(line)).len())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("pattern set", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| patterns.matches(black_box(line)).len())
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_matching);
+criterion_main!(benches);
@@ -0,0 +1,300 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use quarm_audio::TtsEngine;
+
+use crate::{AbortOnDrop, Announcement, DropReason, EventBus, MonitorEvent};
+
+// Separator between announcements joined into one utterance
+const COMBINE_SEPARATOR: &str = ", ";
+
+/// Dispatches immediate announcements to the TTS engine
+/// In combine mode, announcements arriving within a short window are
+/// deduplicated and spoken once as a single phrase, using the first one's style
+pub(crate) struct Announcer {
+    engine: TtsEngine,
+    combine_tx: Option<mpsc::UnboundedSender<Announcement>>,
+    combiner: Option<JoinHandle<()>>,
+    /// Playback tasks that may still be running, so `finish()` can wait for them
+    pending: PendingTasks,
+    events: EventBus,
+}
+
+type PendingTasks = Arc<Mutex<Vec<JoinHandle<()>>>>;
+
+impl Announcer {
+    /// Creates an announcer, spawning the combiner task when a window is given
+    /// The combiner stops once the announcer has been dropped or finished
+    pub(crate) fn start(
+        engine: TtsEngine,
+        combine_window: Option<Duration>,
+        events: EventBus,
+    ) -> Self {
+        let pending = PendingTasks::default();
+        let (combine_tx, combiner) = match combine_window {
+            Some(window) => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                let combiner = tokio::spawn(run_combiner(
+                    rx,
+                    window,
+                    engine.clone(),
+                    Arc::clone(&pending),
+                    events.clone(),
+                ));
+                (Some(tx), Some(combiner))
+            }
+            None => (None, None),
+        };
+
+        Self {
+            engine,
+            combine_tx,
+            combiner,
+            pending,
+            events,
+        }
+    }
+
+    /// Queues an announcement without waiting for playback
+    pub(crate) fn announce(&self, announcement: Announcement) {
+        self.events.publish(MonitorEvent::AnnouncementQueued {
+            text: announcement.text.clone(),
+        });
+        if let Some(tx) = &self.combine_tx
+            && tx.send(announcement.clone()).is_ok()
+        {
+            return;
+        }
+
+        spawn_announce(
+            self.engine.clone(),
+            announcement,
+            &self.pending,
+            &self.events,
+        );
+    }
+
+    /// Waits until everything queued so far has been spoken
+    /// Used when a finite source (like a replay) ends, so the last announcements aren't cut off
+    /// Dropping the returned future part way still stops whatever hasn't been spoken yet
+    pub(crate) async fn finish(mut self) {
+        // Closing the channel lets the combiner flush its last window and stop
+        drop(self.combine_tx.take());
+        if let Some(combiner) = &mut self.combiner {
+            let _ = combiner.await;
+        }
+
+        let mut handles = AbortOnDrop(std::mem::take(&mut *self.pending.lock().unwrap()));
+        for handle in &mut handles.0 {
+            let _ = handle.await;
+        }
+    }
+}
+
+/// Dropping the announcer stops queued announcements; a clip already playing runs to its end
+impl Drop for Announcer {
+    fn drop(&mut self) {
+        if let Some(combiner) = &self.combiner {
+            combiner.abort();
+        }
+        for handle in self.pending.lock().unwrap().iter() {
+            handle.abort();
+        }
+    }
+}
+
+/// Collects announcements in windows and speaks each window's phrase once
+async fn run_combiner(
+    mut rx: mpsc::UnboundedReceiver<Announcement>,
+    window: Duration,
+    engine: TtsEngine,
+    pending: PendingTasks,
+    events: EventBus,
+) {
+    while let Some(announcements) = collect_window(&mut rx, window, &events).await {
+        let phrase = combine(&announcements);
+        if announcements.len() > 1 {
+            println!(
+                "Combined {} announcements: '{}'",
+                announcements.len(),
+                phrase.text
+            );
+        }
+        spawn_announce(engine.clone(), phrase, &pending, &events);
+    }
+}
+
+/// Joins a window's announcements into one phrase styled like the first
+fn combine(announcements: &[Announcement]) -> Announcement {
+    let text = announcements
+        .iter()
+        .map(|announcement| announcement.text.as_str())
+        .collect::<Vec<_>>()
+        .join(COMBINE_SEPARATOR);
+
+    Announcement {
+        text,
+        style: announcements[0].style,
+    }
+}
+
+/// Waits for the first announcement, then gathers everything that arrives
+/// within `window` of it, dropping duplicates while keeping arrival order
+/// Returns None once the channel is closed and drained
+async fn collect_window(
+    rx: &mut mpsc::UnboundedReceiver<Announcement>,
+    window: Duration,
+    events: &EventBus,
+) -> Option<Vec<Announcement>> {
+    let first = rx.recv().await?;
+    let deadline = tokio::time::Instant::now() + window;
+    let mut announcements = vec![first];
+
+    while let Ok(Some(next)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+        if announcements.iter().any(|a| a.text == next.text) {
+            events.publish(MonitorEvent::AnnouncementDropped {
+                text: next.text,
+                reason: DropReason::DuplicateInWindow,
+            });
+        } else {
+            announcements.push(next);
+        }
+    }
+
+    Some(announcements)
+}
+
+/// Speaks an announcement, publishing whether it played
+pub(crate) async fn speak(engine: &TtsEngine, announcement: &Announcement, events: &EventBus) {
+    match engine
+        .announce_styled(&announcement.text, announcement.style)
+        .await
+    {
+        Ok(()) => events.publish(MonitorEvent::AnnouncementPlayed {
+            text: announcement.text.clone(),
+        }),
+        Err(e) => {
+            eprintln!("Failed to announce message: {}", e);
+            events.error(format!(
+                "Failed to announce '{}': {:#}",
+                announcement.text, e
+            ));
+        }
+    }
+}
+
+fn spawn_announce(
+    engine: TtsEngine,
+    announcement: Announcement,
+    pending: &PendingTasks,
+    events: &EventBus,
+) {
+    let events = events.clone();
+    let handle = tokio::spawn(async move { speak(&engine, &announcement, &events).await });
+
+    // Forget finished tasks so a long-running monitor doesn't accumulate handles
+    let mut pending = pending.lock().unwrap();
+    pending.retain(|handle| !handle.is_finished());
+    pending.push(handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quarm_audio::AnnouncementStyle;
+    use quarm_config::TriggerCategory;
+
+    fn announcement(text: &str) -> Announcement {
+        Announcement {
+            text: text.to_string(),
+            style: AnnouncementStyle::default(),
+        }
+    }
+
+    fn texts(announcements: &[Announcement]) -> Vec<&str> {
+        announcements.iter().map(|a| a.text.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_collect_window_deduplicates_in_order() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        for text in ["charm break", "root break", "charm break", "mez break"] {
+            tx.send(announcement(text)).unwrap();
+        }
+
+        let events = EventBus::default();
+        let mut subscriber = events.subscribe();
+        let window = collect_window(&mut rx, Duration::from_millis(20), &events)
+            .await
+            .unwrap();
+        assert_eq!(
+            texts(&window),
+            vec!["charm break", "root break", "mez break"]
+        );
+        assert_eq!(
+            subscriber.try_recv().unwrap(),
+            MonitorEvent::AnnouncementDropped {
+                text: "charm break".to_string(),
+                reason: DropReason::DuplicateInWindow,
+            }
+        );
+    }
+
+    #[test]
+    fn test_combine_uses_first_style() {
+        let mut danger = announcement("charm break");
+        danger.style.category = Some(TriggerCategory::Danger);
+
+        let combined = combine(&[danger, announcement("root break")]);
+        assert_eq!(combined.text, "charm break, root break");
+        assert_eq!(combined.style.category, Some(TriggerCategory::Danger));
+    }
+
+    #[tokio::test]
+    async fn test_collect_window_splits_late_arrivals() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(announcement("charm break")).unwrap();
+
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            tx.send(announcement("root break")).unwrap();
+        });
+
+        let first = collect_window(&mut rx, Duration::from_millis(20), &EventBus::default())
+            .await
+            .unwrap();
+        assert_eq!(texts(&first), vec!["charm break"]);
+
+        let second = collect_window(&mut rx, Duration::from_millis(20), &EventBus::default())
+            .await
+            .unwrap();
+        assert_eq!(texts(&second), vec!["root break"]);
+
+        sender.await.unwrap();
+        assert!(
+            collect_window(&mut rx, Duration::from_millis(20), &EventBus::default())
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finish_waits_for_queued_announcements() {
+        let engine = TtsEngine::new_mock().expect("Failed to create mock TTS engine");
+        let announcer =
+            Announcer::start(engine, Some(Duration::from_millis(20)), EventBus::default());
+        let pending = Arc::clone(&announcer.pending);
+
+        announcer.announce(announcement("charm break"));
+        announcer.announce(announcement("root break"));
+        tokio::time::timeout(Duration::from_secs(30), announcer.finish())
+            .await
+            .expect("finish() should return once the combined phrase is spoken");
+
+        // Nothing is left running
+        assert!(pending.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::LineMatch;
+
+/// Events kept for subscribers that fall behind, before the oldest are skipped
+const EVENT_CAPACITY: usize = 1024;
+
+/// Something the monitor did, published to every subscriber
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MonitorEvent {
+    /// A log started being followed
+    LogOpened { path: PathBuf, character: String },
+    /// A log stopped being followed (another log became the newest, or it was deleted)
+    LogClosed { path: PathBuf, character: String },
+    /// A followed log was truncated or replaced and is being read from the start
+    LogReset { path: PathBuf, character: String },
+    /// A character entered a zone
+    ZoneChanged { character: String, zone: String },
+    /// A trigger matched a log line
+    LineMatched(LineMatch),
+    /// An announcement was queued for playback
+    AnnouncementQueued { text: String },
+    /// An announcement finished playing (combined announcements play as one)
+    AnnouncementPlayed { text: String },
+    /// An announcement was not played
+    AnnouncementDropped { text: String, reason: DropReason },
+    /// A timer was started for a character's timed trigger, or moved to a new deadline
+    TimerScheduled {
+        id: u64,
+        character: String,
+        pattern: String,
+        announcement: String,
+        delay_secs: f64,
+        /// When the timer will fire
+        deadline: DateTime<Utc>,
+    },
+    /// A running timer was stopped before firing
+    TimerCancelled {
+        id: u64,
+        character: String,
+        pattern: String,
+    },
+    /// A timer ran out and its announcement is being played
+    TimerFired {
+        id: u64,
+        character: String,
+        pattern: String,
+        announcement: String,
+    },
+    /// Something went wrong; monitoring may or may not continue
+    Error { message: String },
+}
+
+/// Why an announcement was not played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    /// Already queued from an earlier line of the same batch
+    DuplicateInBatch,
+    /// Already part of the phrase being combined
+    DuplicateInWindow,
+    /// Its line was read long after it was written, e.g. while catching up after a stall
+    Stale,
+}
+
+/// Broadcasts monitor events to any number of subscribers
+/// Publishing never blocks, and events are discarded when nobody is subscribed
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<MonitorEvent>,
+}
+
+impl EventBus {
+    /// Subscribes to events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<MonitorEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn publish(&self, event: MonitorEvent) {
+        // Only fails when there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    pub(crate) fn error(&self, message: String) {
+        self.publish(MonitorEvent::Error { message });
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_reach_every_subscriber() {
+        let bus = EventBus::default();
+        // Publishing without subscribers is fine
+        bus.error("nobody listening".to_string());
+
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+        bus.publish(MonitorEvent::AnnouncementQueued {
+            text: "charm break".to_string(),
+        });
+
+        for receiver in [&mut first, &mut second] {
+            assert_eq!(
+                receiver.try_recv().unwrap(),
+                MonitorEvent::AnnouncementQueued {
+                    text: "charm break".to_string()
+                }
+            );
+            assert!(receiver.try_recv().is_err());
+        }
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = MonitorEvent::AnnouncementDropped {
+            text: "charm break".to_string(),
+            reason: DropReason::DuplicateInWindow,
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "announcement_dropped");
+        assert_eq!(value["reason"], "duplicate_in_window");
+    }
+}
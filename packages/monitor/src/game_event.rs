@@ -0,0 +1,502 @@
+use quarm_config::{ChatChannel, EventCondition, EventKind};
+
+// Melee verbs EverQuest uses in "X <verb> Y for N points of damage." lines
+const DAMAGE_VERBS: &[&str] = &[
+    "hit",
+    "hits",
+    "slash",
+    "slashes",
+    "crush",
+    "crushes",
+    "pierce",
+    "pierces",
+    "bash",
+    "bashes",
+    "kick",
+    "kicks",
+    "bite",
+    "bites",
+    "claw",
+    "claws",
+    "punch",
+    "punches",
+    "backstab",
+    "backstabs",
+    "maul",
+    "mauls",
+    "gore",
+    "gores",
+    "sting",
+    "stings",
+    "slice",
+    "slices",
+    "strike",
+    "strikes",
+    "smash",
+    "smashes",
+    "rend",
+    "rends",
+    "frenzy on",
+    "frenzies on",
+];
+
+// Chat line markers, checked in order; the speaker is everything before the marker
+const CHAT_MARKERS: &[(&str, ChatChannel)] = &[
+    (" tells the group, '", ChatChannel::Group),
+    (" tells the guild, '", ChatChannel::Guild),
+    (" tells the raid, '", ChatChannel::Raid),
+    (" says out of character, '", ChatChannel::Ooc),
+    (" shouts, '", ChatChannel::Shout),
+    (" auctions, '", ChatChannel::Auction),
+    (" says, '", ChatChannel::Say),
+];
+
+/// A log line classified into a common EverQuest message type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    Tell {
+        speaker: String,
+        text: String,
+    },
+    Chat {
+        channel: ChatChannel,
+        speaker: String,
+        text: String,
+    },
+    /// `killer` is "You" for your own kills; `victim` is "You" for your deaths
+    Slain {
+        victim: String,
+        killer: Option<String>,
+    },
+    ZoneEntered {
+        zone: String,
+    },
+    SpellWornOff {
+        spell: String,
+    },
+    /// `target` is "You" when you resisted, "Your target" when your spell was resisted
+    SpellResisted {
+        spell: String,
+        target: String,
+    },
+    /// `attacker` is None for non-melee damage
+    Damage {
+        attacker: Option<String>,
+        target: String,
+        amount: u32,
+    },
+}
+
+impl GameEvent {
+    /// Classifies a log line body, returning None for lines of no known type
+    pub fn classify(body: &str) -> Option<Self> {
+        let body = body.trim();
+
+        classify_chat(body)
+            .or_else(|| classify_slain(body))
+            .or_else(|| classify_zone(body))
+            .or_else(|| classify_spell(body))
+            .or_else(|| classify_damage(body))
+    }
+
+    /// Returns the kind of this event
+    pub fn kind(&self) -> EventKind {
+        match self {
+            GameEvent::Tell { .. } => EventKind::Tell,
+            GameEvent::Chat { .. } => EventKind::Chat,
+            GameEvent::Slain { .. } => EventKind::Slain,
+            GameEvent::ZoneEntered { .. } => EventKind::ZoneEntered,
+            GameEvent::SpellWornOff { .. } => EventKind::SpellWornOff,
+            GameEvent::SpellResisted { .. } => EventKind::SpellResisted,
+            GameEvent::Damage { .. } => EventKind::Damage,
+        }
+    }
+
+    /// Checks whether this event satisfies every field set on a condition
+    /// A field that doesn't apply to this event's kind never matches
+    pub fn satisfies(&self, condition: &EventCondition) -> bool {
+        if self.kind() != condition.kind {
+            return false;
+        }
+
+        let (speaker, channel, zone, spell, target, text, amount) = match self {
+            GameEvent::Tell { speaker, text } => (
+                Some(speaker.as_str()),
+                None,
+                None,
+                None,
+                None,
+                Some(text.as_str()),
+                None,
+            ),
+            GameEvent::Chat {
+                channel,
+                speaker,
+                text,
+            } => (
+                Some(speaker.as_str()),
+                Some(*channel),
+                None,
+                None,
+                None,
+                Some(text.as_str()),
+                None,
+            ),
+            GameEvent::Slain { victim, killer } => (
+                killer.as_deref(),
+                None,
+                None,
+                None,
+                Some(victim.as_str()),
+                None,
+                None,
+            ),
+            GameEvent::ZoneEntered { zone } => {
+                (None, None, Some(zone.as_str()), None, None, None, None)
+            }
+            GameEvent::SpellWornOff { spell } => {
+                (None, None, None, Some(spell.as_str()), None, None, None)
+            }
+            GameEvent::SpellResisted { spell, target } => (
+                None,
+                None,
+                None,
+                Some(spell.as_str()),
+                Some(target.as_str()),
+                None,
+                None,
+            ),
+            GameEvent::Damage {
+                attacker,
+                target,
+                amount,
+            } => (
+                attacker.as_deref(),
+                None,
+                None,
+                None,
+                Some(target.as_str()),
+                None,
+                Some(*amount),
+            ),
+        };
+
+        names_match(&condition.speaker, speaker)
+            && condition
+                .channel
+                .is_none_or(|wanted| channel == Some(wanted))
+            && names_match(&condition.zone, zone)
+            && names_match(&condition.spell, spell)
+            && names_match(&condition.target, target)
+            && condition.text_contains.as_ref().is_none_or(|wanted| {
+                text.is_some_and(|text| text.to_lowercase().contains(&wanted.to_lowercase()))
+            })
+            && condition
+                .min_amount
+                .is_none_or(|min| amount.is_some_and(|amount| amount >= min))
+    }
+}
+
+/// An unset condition matches anything; a set one needs an equal (case-insensitive) value
+fn names_match(wanted: &Option<String>, actual: Option<&str>) -> bool {
+    match wanted {
+        None => true,
+        Some(wanted) => actual.is_some_and(|actual| actual.eq_ignore_ascii_case(wanted)),
+    }
+}
+
+fn classify_chat(body: &str) -> Option<GameEvent> {
+    let text = body.strip_suffix('\'')?;
+
+    if let Some((speaker, text)) = text.split_once(" tells you, '") {
+        return Some(GameEvent::Tell {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+        });
+    }
+
+    CHAT_MARKERS.iter().find_map(|(marker, channel)| {
+        let (speaker, text) = text.split_once(marker)?;
+        // Speakers are single names; this skips "You say, '...'"-style echoes of your own chat
+        (!speaker.contains(' ') && speaker != "You").then(|| GameEvent::Chat {
+            channel: *channel,
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+        })
+    })
+}
+
+fn classify_slain(body: &str) -> Option<GameEvent> {
+    if let Some(victim) = body
+        .strip_prefix("You have slain ")
+        .and_then(|rest| rest.strip_suffix('!'))
+    {
+        return Some(GameEvent::Slain {
+            victim: victim.to_string(),
+            killer: Some("You".to_string()),
+        });
+    }
+
+    if let Some(victim) = body.strip_suffix(" died.") {
+        return Some(GameEvent::Slain {
+            victim: victim.to_string(),
+            killer: None,
+        });
+    }
+
+    let (victim, killer) = body.strip_suffix('!')?.split_once(" been slain by ")?;
+    let victim = victim
+        .strip_suffix(" has")
+        .or_else(|| victim.strip_suffix(" have"))?;
+    Some(GameEvent::Slain {
+        victim: victim.to_string(),
+        killer: Some(killer.to_string()),
+    })
+}
+
+fn classify_zone(body: &str) -> Option<GameEvent> {
+    Some(GameEvent::ZoneEntered {
+        zone: zone_entered(body)?.to_string(),
+    })
+}
+
+/// Returns the zone name from a "You have entered X." line body
+/// Area messages worded the same way, like "You have entered an Arena (PvP) area.", aren't
+/// zone changes
+pub(crate) fn zone_entered(body: &str) -> Option<&str> {
+    let zone = body
+        .trim()
+        .strip_prefix("You have entered ")?
+        .strip_suffix('.')?;
+    if zone.starts_with("an area ") || zone.ends_with(" area") {
+        return None;
+    }
+    Some(zone)
+}
+
+fn classify_spell(body: &str) -> Option<GameEvent> {
+    if let Some(spell) = body
+        .strip_prefix("Your ")
+        .and_then(|rest| rest.strip_suffix(" spell has worn off."))
+    {
+        return Some(GameEvent::SpellWornOff {
+            spell: spell.to_string(),
+        });
+    }
+
+    if let Some(spell) = body
+        .strip_prefix("Your target resisted the ")
+        .and_then(|rest| rest.strip_suffix(" spell."))
+    {
+        return Some(GameEvent::SpellResisted {
+            spell: spell.to_string(),
+            target: "Your target".to_string(),
+        });
+    }
+
+    let spell = body
+        .strip_prefix("You resist the ")?
+        .strip_suffix(" spell!")?;
+    Some(GameEvent::SpellResisted {
+        spell: spell.to_string(),
+        target: "You".to_string(),
+    })
+}
+
+fn classify_damage(body: &str) -> Option<GameEvent> {
+    let (action, amount) = body.rsplit_once(" for ")?;
+    let amount = amount
+        .strip_suffix(" points of damage.")
+        .or_else(|| amount.strip_suffix(" point of damage."))?
+        .parse()
+        .ok()?;
+
+    if let Some(target) = action.strip_suffix(" was hit by non-melee") {
+        return Some(GameEvent::Damage {
+            attacker: None,
+            target: target.to_string(),
+            amount,
+        });
+    }
+
+    // Find the verb splitting attacker from target, e.g. "a fire beetle bites YOU"
+    DAMAGE_VERBS.iter().find_map(|verb| {
+        let (attacker, target) = action.split_once(&format!(" {} ", verb))?;
+        Some(GameEvent::Damage {
+            attacker: Some(attacker.to_string()),
+            target: target.to_string(),
+            amount,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_tells_and_chat() {
+        assert_eq!(
+            GameEvent::classify("Soandso tells you, 'can I get a port?'"),
+            Some(GameEvent::Tell {
+                speaker: "Soandso".to_string(),
+                text: "can I get a port?".to_string(),
+            })
+        );
+
+        let channels = [
+            ("Soandso tells the group, 'inc'", ChatChannel::Group),
+            ("Soandso tells the guild, 'raid at 8'", ChatChannel::Guild),
+            ("Soandso tells the raid, 'AE now'", ChatChannel::Raid),
+            ("Soandso says out of character, 'lfg'", ChatChannel::Ooc),
+            ("Soandso shouts, 'train to zone!'", ChatChannel::Shout),
+            (
+                "Soandso auctions, 'WTS Cloak of Flames'",
+                ChatChannel::Auction,
+            ),
+            ("Soandso says, 'Hail, Guard'", ChatChannel::Say),
+        ];
+        for (body, expected) in channels {
+            match GameEvent::classify(body) {
+                Some(GameEvent::Chat {
+                    channel, speaker, ..
+                }) => {
+                    assert_eq!(channel, expected, "channel of {:?}", body);
+                    assert_eq!(speaker, "Soandso");
+                }
+                other => panic!("{:?} classified as {:?}", body, other),
+            }
+        }
+
+        // Your own chat echoes are not classified as someone speaking
+        assert_eq!(GameEvent::classify("You say, 'Hail, Guard'"), None);
+    }
+
+    #[test]
+    fn test_classifies_slain_zone_and_spells() {
+        assert_eq!(
+            GameEvent::classify("a gnoll pup has been slain by Soandso!"),
+            Some(GameEvent::Slain {
+                victim: "a gnoll pup".to_string(),
+                killer: Some("Soandso".to_string()),
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("You have been slain by an iksar betrayer!"),
+            Some(GameEvent::Slain {
+                victim: "You".to_string(),
+                killer: Some("an iksar betrayer".to_string()),
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("You have slain a fire beetle!"),
+            Some(GameEvent::Slain {
+                victim: "a fire beetle".to_string(),
+                killer: Some("You".to_string()),
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("You have entered The Plane of Fear."),
+            Some(GameEvent::ZoneEntered {
+                zone: "The Plane of Fear".to_string()
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("You have entered The Arena."),
+            Some(GameEvent::ZoneEntered {
+                zone: "The Arena".to_string()
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("Your Charm spell has worn off."),
+            Some(GameEvent::SpellWornOff {
+                spell: "Charm".to_string()
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("Your target resisted the Enthrall spell."),
+            Some(GameEvent::SpellResisted {
+                spell: "Enthrall".to_string(),
+                target: "Your target".to_string(),
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("You resist the Lure of Ice spell!"),
+            Some(GameEvent::SpellResisted {
+                spell: "Lure of Ice".to_string(),
+                target: "You".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_classifies_damage() {
+        assert_eq!(
+            GameEvent::classify("a fire beetle bites YOU for 12 points of damage."),
+            Some(GameEvent::Damage {
+                attacker: Some("a fire beetle".to_string()),
+                target: "YOU".to_string(),
+                amount: 12,
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("You slash a gnoll pup for 1 point of damage."),
+            Some(GameEvent::Damage {
+                attacker: Some("You".to_string()),
+                target: "a gnoll pup".to_string(),
+                amount: 1,
+            })
+        );
+        assert_eq!(
+            GameEvent::classify("a gnoll pup was hit by non-melee for 48 points of damage."),
+            Some(GameEvent::Damage {
+                attacker: None,
+                target: "a gnoll pup".to_string(),
+                amount: 48,
+            })
+        );
+    }
+
+    #[test]
+    fn test_unclassified_lines() {
+        for body in [
+            "",
+            "You feel less charming.",
+            "Soandso hits YOU for lots of damage.",
+            "You have entered",
+            "You have entered an area where levitation effects do not function.",
+            "You have entered an Arena (PvP) area.",
+        ] {
+            assert_eq!(GameEvent::classify(body), None, "{:?}", body);
+        }
+    }
+
+    #[test]
+    fn test_satisfies_conditions() {
+        let tell = GameEvent::classify("Soandso tells you, 'Need a rez please'").unwrap();
+
+        let mut condition = EventCondition::kind(EventKind::Tell);
+        assert!(tell.satisfies(&condition));
+
+        condition.speaker = Some("soandso".to_string());
+        condition.text_contains = Some("REZ".to_string());
+        assert!(tell.satisfies(&condition));
+
+        condition.speaker = Some("Someoneelse".to_string());
+        assert!(!tell.satisfies(&condition));
+
+        // Fields that don't apply to the event kind never match
+        let mut condition = EventCondition::kind(EventKind::Tell);
+        condition.zone = Some("Nektulos Forest".to_string());
+        assert!(!tell.satisfies(&condition));
+
+        let hit = GameEvent::classify("a fire beetle bites YOU for 12 points of damage.").unwrap();
+        let mut condition = EventCondition::kind(EventKind::Damage);
+        condition.target = Some("you".to_string());
+        condition.min_amount = Some(10);
+        assert!(hit.satisfies(&condition));
+        condition.min_amount = Some(100);
+        assert!(!hit.satisfies(&condition));
+        assert!(!hit.satisfies(&EventCondition::kind(EventKind::Chat)));
+    }
+}
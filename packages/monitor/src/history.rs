@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+// Size of each chunk read backwards when searching log history
+pub(crate) const HISTORY_CHUNK_SIZE: u64 = 64 * 1024;
+
+// How far back from where a search starts to look through a log
+const MAX_HISTORY_SCAN: u64 = 16 * 1024 * 1024;
+
+/// Walks a log's lines backwards from `end`, calling `visit` with each line (without its
+/// newline) and the offset it starts at, until `visit` returns true
+/// Only the last `MAX_HISTORY_SCAN` bytes before `end` are searched; a line cut off by that
+/// limit is skipped
+pub(crate) fn scan_back(
+    path: &Path,
+    end: u64,
+    mut visit: impl FnMut(&[u8], u64) -> bool,
+) -> Result<()> {
+    let mut file = File::open(path).context(format!("Failed to open: {}", path.display()))?;
+
+    let scan_start = end.saturating_sub(MAX_HISTORY_SCAN);
+    let mut chunk_end = end;
+    // Partial first line of the previously read chunk, completed by the next chunk back
+    let mut carry: Vec<u8> = Vec::new();
+
+    while chunk_end > scan_start {
+        let start = chunk_end.saturating_sub(HISTORY_CHUNK_SIZE).max(scan_start);
+        let mut chunk = vec![0; (chunk_end - start) as usize];
+        file.seek(SeekFrom::Start(start))
+            .context("Failed to seek in log file")?;
+        file.read_exact(&mut chunk)
+            .context("Failed to read log file")?;
+        chunk.extend_from_slice(&carry);
+
+        // Unless this chunk starts the file, its first line may be cut off
+        let split = if start == 0 {
+            0
+        } else {
+            chunk
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(chunk.len(), |i| i + 1)
+        };
+        let (partial, complete) = chunk.split_at(split);
+
+        let mut line_end = complete.len();
+        for raw in complete.rsplit(|&b| b == b'\n') {
+            let line_start = line_end - raw.len();
+            if visit(raw, start + (split + line_start) as u64) {
+                return Ok(());
+            }
+            line_end = line_start.saturating_sub(1);
+        }
+
+        // Without its newline, so joined to the chunk before it doesn't end in an empty line
+        carry = partial.strip_suffix(b"\n").unwrap_or(partial).to_vec();
+        chunk_end = start;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_back_offsets() {
+        let path = std::env::temp_dir().join(format!("quarm_scan_back_{}.txt", std::process::id()));
+        let mut log = String::new();
+        let mut offsets = Vec::new();
+        // Enough lines to span several chunks
+        for i in 0..10_000 {
+            offsets.push(log.len() as u64);
+            log.push_str(&format!("[Fri Oct 16 12:00:00 2026] line {}\n", i));
+        }
+        std::fs::write(&path, &log).unwrap();
+
+        let mut seen = Vec::new();
+        scan_back(&path, log.len() as u64, |raw, offset| {
+            seen.push((String::from_utf8_lossy(raw).into_owned(), offset));
+            false
+        })
+        .unwrap();
+
+        // The empty remainder after the final newline comes first
+        assert_eq!(seen[0], (String::new(), log.len() as u64));
+        assert_eq!(seen.len(), offsets.len() + 1);
+        for ((line, offset), expected) in seen[1..].iter().zip(offsets.iter().rev()) {
+            assert_eq!(offset, expected);
+            assert_eq!(&log[*offset as usize..][..line.len()], line);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
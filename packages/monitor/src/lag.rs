@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime};
+use serde::Serialize;
+
+/// How far behind the logs the monitor has been, measured from each line's timestamp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LagStats {
+    /// Timestamped lines read so far
+    pub lines: u64,
+    /// Time between writing and reading the latest line
+    pub last_secs: f64,
+    /// Longest time between writing and reading a line
+    pub max_secs: f64,
+    /// Lines read after `max_line_lag_secs`
+    pub stale_lines: u64,
+}
+
+/// Shared handle to the monitor's lag statistics
+/// Cloning shares the same underlying statistics
+#[derive(Debug, Clone, Default)]
+pub struct LagTracker {
+    stats: Arc<Mutex<LagStats>>,
+}
+
+impl LagTracker {
+    /// Returns the statistics so far
+    pub fn stats(&self) -> LagStats {
+        *self.stats.lock().unwrap()
+    }
+
+    pub(crate) fn record(&self, lag: Duration, stale: bool) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.lines += 1;
+        stats.last_secs = lag.as_secs_f64();
+        stats.max_secs = stats.max_secs.max(stats.last_secs);
+        if stale {
+            stats.stale_lines += 1;
+        }
+    }
+}
+
+/// How long ago a line with this timestamp was written
+/// Log timestamps are local time; ones in the future (clock changes) count as no lag
+pub(crate) fn line_lag(timestamp: NaiveDateTime) -> Duration {
+    (Local::now().naive_local() - timestamp)
+        .to_std()
+        .unwrap_or(Duration::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn test_records_lag() {
+        let lag = LagTracker::default();
+        lag.record(Duration::from_secs(45), true);
+        lag.record(Duration::from_millis(500), false);
+
+        let stats = lag.stats();
+        assert_eq!(stats.lines, 2);
+        assert_eq!(stats.last_secs, 0.5);
+        assert_eq!(stats.max_secs, 45.0);
+        assert_eq!(stats.stale_lines, 1);
+    }
+
+    #[test]
+    fn test_line_lag() {
+        let written = Local::now().naive_local() - TimeDelta::seconds(90);
+        let lag = line_lag(written);
+        assert!(lag >= Duration::from_secs(90) && lag < Duration::from_secs(95));
+
+        let future = Local::now().naive_local() + TimeDelta::seconds(90);
+        assert_eq!(line_lag(future), Duration::ZERO);
+    }
+}
@@ -1,18 +1,54 @@
 use std::collections::{HashMap, HashSet};
-use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, Result};
-use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader};
-use tokio::task::JoinHandle;
-
-use quarm_audio::TtsEngine;
-use quarm_config::{Config, MessageConfig};
+use chrono::NaiveDateTime;
+use encoding_rs::Encoding;
+use serde::Serialize;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+use quarm_audio::{AnnouncementStyle, TtsEngine};
+use quarm_config::{
+    CharacterProfile, Config, LateTimerPolicy, MessageConfig, StaleLinePolicy, StartPosition,
+};
+
+mod announcer;
+mod events;
+mod game_event;
+mod history;
+mod lag;
+mod log_line;
+mod matcher;
+mod patterns;
+mod replay;
+mod report;
+mod start;
+mod tailer;
+mod timers;
+mod watcher;
+mod zone;
+
+use announcer::Announcer;
+use matcher::TriggerMatcher;
+use replay::{Pacer, RangePosition};
+use start::Bookmarks;
+use tailer::{LineBuffer, LogTailer};
+use watcher::{LogWatcher, Wake};
+
+pub use events::{DropReason, EventBus, MonitorEvent};
+pub use game_event::GameEvent;
+pub use lag::{LagStats, LagTracker};
+pub use log_line::LogLine;
+pub use patterns::PatternSet;
+pub use replay::{ReplayOptions, ReplaySpeed, ReplaySummary, parse_replay_time};
+pub use report::{DryRunReport, ReportMatch, ReportTimer, TriggerCount, dry_run};
+pub use timers::{TimerInfo, Timers};
+pub use tokio_util::sync::CancellationToken;
+pub use zone::ZoneTracker;
 
 // Prefix for log files we're interested in
-const LOG_FILE_PREFIX: &str = "eqlog_";
+pub(crate) const LOG_FILE_PREFIX: &str = "eqlog_";
 
 // Interval for checking if a different log file has become most recent
 const MTIME_CHECK_INTERVAL: Duration = Duration::from_secs(1);
@@ -20,223 +56,703 @@ const MTIME_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 // Timeout for checking if more lines are immediately available when batching
 const BATCH_READ_TIMEOUT: Duration = Duration::from_millis(10);
 
-// Wait time when no data is available (EOF reached)
-const IDLE_RETRY_DELAY: Duration = Duration::from_millis(50);
+// How long a deleted log keeps being watched for a replacement at the same path
+const MISSING_LOG_GRACE: Duration = Duration::from_secs(30);
+
+// Wait time when no data is available (EOF reached) and file events aren't available
+pub(crate) const IDLE_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// Tasks that are aborted if they're dropped before finishing, e.g. when waiting for them
+/// is cancelled part way
+pub(crate) struct AbortOnDrop(pub(crate) Vec<tokio::task::JoinHandle<()>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        for handle in &self.0 {
+            handle.abort();
+        }
+    }
+}
+
+/// Replaces a file in one step, by writing the contents beside it and renaming them over it,
+/// so a crash never leaves it half written
+pub(crate) fn replace_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let temp = path.with_extension("tmp");
+    std::fs::write(&temp, contents).context(format!("Failed to write: {}", temp.display()))?;
+    std::fs::rename(&temp, path).context(format!("Failed to replace: {}", path.display()))
+}
+
+/// Lists the eqlog_* files in the given directory with their modification times.
+fn list_logs(directory: &Path) -> Result<Vec<(PathBuf, SystemTime)>> {
+    let entries = std::fs::read_dir(directory)
+        .context(format!("Failed to read directory: {}", directory.display()))?;
+
+    Ok(entries
+        .filter_map(|e| e.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(LOG_FILE_PREFIX)
+        })
+        .filter_map(|entry| {
+            let mtime = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.path(), mtime))
+        })
+        .collect())
+}
 
 /// Scans the given directory for eqlog_* files and returns the most recently modified one.
 /// Returns None if no matching log files are found.
 fn find_most_recent_log(directory: &Path) -> Result<Option<PathBuf>> {
-    let entries = std::fs::read_dir(directory)
-        .context(format!("Failed to read directory: {}", directory.display()))?;
+    Ok(list_logs(directory)?
+        .into_iter()
+        .max_by_key(|(_, mtime)| *mtime)
+        .map(|(path, _)| path))
+}
 
-    let mut most_recent: Option<(PathBuf, std::time::SystemTime)> = None;
+/// Scans the given directory for eqlog_* files modified within `window`.
+/// Falls back to the most recently modified file when none are that recent.
+fn find_active_logs(directory: &Path, window: Duration) -> Result<Vec<PathBuf>> {
+    let logs = list_logs(directory)?;
+    let now = SystemTime::now();
 
-    for entry in entries.filter_map(|e| e.ok()) {
-        let file_name = entry.file_name();
-        let name_str = file_name.to_string_lossy();
+    let mut active: Vec<PathBuf> = logs
+        .iter()
+        .filter(|(_, mtime)| now.duration_since(*mtime).unwrap_or_default() <= window)
+        .map(|(path, _)| path.clone())
+        .collect();
 
-        if name_str.starts_with(LOG_FILE_PREFIX)
-            && let Ok(metadata) = entry.metadata()
-                && let Ok(mtime) = metadata.modified() {
-                    match &most_recent {
-                        None => most_recent = Some((entry.path(), mtime)),
-                        Some((_, prev_mtime)) if mtime > *prev_mtime => {
-                            most_recent = Some((entry.path(), mtime));
-                        }
-                        _ => {}
-                    }
-                }
+    if active.is_empty()
+        && let Some((path, _)) = logs.into_iter().max_by_key(|(_, mtime)| *mtime)
+    {
+        active.push(path);
     }
+    active.sort();
 
-    Ok(most_recent.map(|(path, _)| path))
+    Ok(active)
+}
+
+/// An announcement to speak, with the rendering style of the trigger that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Announcement {
+    text: String,
+    style: AnnouncementStyle,
+}
+
+impl Announcement {
+    fn for_message(message: &MessageConfig) -> Self {
+        Self {
+            text: message.announcement().to_string(),
+            style: AnnouncementStyle::for_message(message),
+        }
+    }
+
+    /// Returns the announcement for a message matched in a character's log, using the
+    /// profile's voice and prefix (or the character name when `prefix_character_name` is set)
+    fn for_character(
+        message: &MessageConfig,
+        character: &str,
+        profile: Option<&CharacterProfile>,
+        prefix_character_name: bool,
+    ) -> Self {
+        let mut announcement = Self::for_message(message);
+        let prefix = profile
+            .and_then(|profile| profile.prefix.as_deref())
+            .or(prefix_character_name.then_some(character));
+        if let Some(prefix) = prefix {
+            announcement.text = format!("{}, {}", prefix, announcement.text);
+        }
+        announcement.style.speaker = profile.and_then(|profile| profile.speaker);
+        announcement
+    }
+}
+
+/// Returns every text and style the monitor can announce for a config, for precaching
+/// Character name prefixes of characters without a profile are not included
+pub fn announcement_variants(config: &Config) -> Vec<(String, AnnouncementStyle)> {
+    let mut variants: Vec<Announcement> = config
+        .messages
+        .iter()
+        .map(Announcement::for_message)
+        .collect();
+    for (character, profile) in &config.profiles.by_character {
+        for message in config.messages.iter().filter(|m| profile.enables(m)) {
+            variants.push(Announcement::for_character(
+                message,
+                character,
+                Some(profile),
+                config.monitor.prefix_character_name,
+            ));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    variants
+        .into_iter()
+        .filter(|announcement| seen.insert(announcement.clone()))
+        .map(|announcement| (announcement.text, announcement.style))
+        .collect()
+}
+
+impl PartialEq<str> for Announcement {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+/// A configured message that matched a log line, with the line's timestamp
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineMatch {
+    /// Index of the matched trigger in the config's messages
+    pub trigger: usize,
+    /// Character whose log the line came from
+    pub character: String,
+    /// When the matched line was written, if its timestamp could be parsed
+    pub timestamp: Option<NaiveDateTime>,
+    /// Message body of the matched line
+    pub body: String,
+    pub pattern: String,
+    pub announcement: String,
 }
 
 /// Result of processing a batch of log lines
+#[derive(Default)]
 struct BatchResult {
+    /// Every match in the batch, in log order (not deduplicated)
+    matches: Vec<LineMatch>,
     /// Immediate announcements to play now (Simple message types)
-    immediate: Vec<String>,
-    /// Timed delay announcements: pattern -> (announcement, delay_seconds)
-    /// Pattern is used as key for batch-level deduplication
-    timed_delay: HashMap<String, (String, u64)>,
+    /// Deduplicated, in order of first appearance
+    immediate: Vec<Announcement>,
+    /// Timed delay announcements, keyed by trigger index for batch-level deduplication
+    /// (event-only triggers all have an empty pattern, so the pattern can't be the key)
+    timed_delay: HashMap<usize, TimedMatch>,
+}
+
+/// A timed delay trigger that matched, waiting to be scheduled
+struct TimedMatch {
+    /// Pattern of the trigger, shown with the timer
+    pattern: String,
+    announcement: Announcement,
+    delay_seconds: u64,
+    /// When the matching line was written, if it had a timestamp
+    written: Option<NaiveDateTime>,
+}
+
+impl BatchResult {
+    /// Counts timers from when their line was written rather than when it was read,
+    /// so they still fire on time when the monitor has fallen behind
+    /// Timers that were already due when their line was read are taken out and returned
+    fn anchor_timers(&mut self) -> Vec<Announcement> {
+        let mut expired = Vec::new();
+        self.timed_delay.retain(|_, timed| {
+            let Some(written) = timed.written else {
+                return true;
+            };
+            match timed
+                .delay_seconds
+                .checked_sub(lag::line_lag(written).as_secs())
+            {
+                Some(left) => {
+                    timed.delay_seconds = left;
+                    true
+                }
+                None => {
+                    expired.push(timed.announcement.clone());
+                    false
+                }
+            }
+        });
+        expired
+    }
 }
 
 pub struct LogMonitor {
     game_directory: PathBuf,
-    messages: Vec<MessageConfig>,
+    /// Trigger matching and zone tracking
+    matcher: TriggerMatcher,
     tts_engine: TtsEngine,
-    /// Active timers tracked by pattern string
-    /// Key: pattern, Value: JoinHandle for the timer task
-    active_timers: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
+    /// Window for combining simultaneous announcements (None speaks each separately)
+    combine_window: Option<Duration>,
+    /// Tail every log written within this window, rather than only the newest
+    all_characters_window: Option<Duration>,
+    /// Timed delay announcements waiting to fire, by character and pattern
+    timers: Timers,
+    /// File timers are saved to while monitoring, so they survive a restart
+    timer_state_file: Option<PathBuf>,
+    /// What to do with saved timers that ran out while monitoring was stopped
+    late_timers: LateTimerPolicy,
+    /// Leave pending timers in the state file when monitoring stops, rather than cancelling
+    keep_timers_on_stop: bool,
+    /// Lines read longer than this after they were written are stale (None: never)
+    max_line_lag: Option<Duration>,
+    /// What to do with matches on stale lines
+    stale_lines: StaleLinePolicy,
+    /// How far behind the logs monitoring is
+    lag: LagTracker,
+    /// Where to start reading the logs found when monitoring starts
+    start_position: StartPosition,
+    /// File the position reached in each log is saved to when monitoring stops
+    bookmark_file: Option<PathBuf>,
+    /// Encoding the logs are written in
+    encoding: &'static Encoding,
 }
 
 impl LogMonitor {
     /// Creates a new LogMonitor from config and TTS engine
-    pub fn new(config: Config, tts_engine: TtsEngine) -> Self {
-        Self {
-            game_directory: PathBuf::from(config.game_directory),
-            messages: config.messages,
+    /// Fails if a regex trigger pattern or the log encoding is invalid
+    pub fn new(config: Config, tts_engine: TtsEngine) -> Result<Self> {
+        let encoding = log_line::log_encoding(&config.monitor.log_encoding)?;
+        let game_directory = PathBuf::from(&config.game_directory);
+        let timer_state_file = config.monitor.timer_state_file.as_ref().map(PathBuf::from);
+        let late_timers = config.monitor.late_timers;
+        let keep_timers_on_stop = config.monitor.keep_timers_on_stop;
+        let max_line_lag = config.monitor.max_line_lag_secs.map(Duration::from_secs);
+        let stale_lines = config.monitor.stale_lines;
+        let start_position = config.monitor.start_position;
+        let bookmark_file = config.monitor.bookmark_file.as_ref().map(PathBuf::from);
+        let combine_window = config
+            .monitor
+            .combine_announcements
+            .then(|| Duration::from_millis(config.monitor.combine_window_ms));
+        let all_characters_window = config
+            .monitor
+            .all_characters
+            .then(|| Duration::from_secs(config.monitor.active_log_window_secs));
+        let matcher = TriggerMatcher::new(config)?;
+
+        Ok(Self {
+            game_directory,
+            timers: Timers::new(tts_engine.clone(), matcher.events.clone()),
             tts_engine,
-            active_timers: Arc::new(Mutex::new(HashMap::new())),
-        }
+            combine_window,
+            all_characters_window,
+            matcher,
+            timer_state_file,
+            late_timers,
+            keep_timers_on_stop,
+            max_line_lag,
+            stale_lines,
+            lag: LagTracker::default(),
+            start_position,
+            bookmark_file,
+            encoding,
+        })
+    }
+
+    /// Returns a handle to the current zone, which stays live while monitoring
+    pub fn zone_tracker(&self) -> ZoneTracker {
+        self.matcher.zone.clone()
+    }
+
+    /// Returns a handle to the running timers, for listing and managing them
+    pub fn timers(&self) -> Timers {
+        self.timers.clone()
+    }
+
+    /// Returns the bus the monitor publishes its events on, for subscribing
+    pub fn events(&self) -> EventBus {
+        self.matcher.events.clone()
+    }
+
+    /// Returns a handle to how far behind the logs monitoring is, which stays live
+    pub fn lag(&self) -> LagTracker {
+        self.lag.clone()
     }
 
     /// Starts monitoring log files for configured messages
-    /// Automatically tracks the most recently modified eqlog_* file
-    /// This function runs forever until an error occurs or the program is terminated
-    pub async fn start_monitoring(&self) -> Result<()> {
+    /// Automatically tracks the most recently modified eqlog_* file, or every recently
+    /// written one when monitoring all characters
+    /// Runs until `shutdown` is cancelled or an error occurs; either way queued announcements
+    /// are dropped and timers are stopped before returning, so nothing more is spoken
+    pub async fn start_monitoring(&self, shutdown: CancellationToken) -> Result<()> {
         println!("Scanning directory: {:?}", self.game_directory);
 
+        // Pick up timers from before a restart; replays never touch the state file
+        if let Some(path) = &self.timer_state_file
+            && let Err(e) = self.timers.restore(path, self.late_timers)
+        {
+            eprintln!("Failed to restore timers: {:#}", e);
+            self.matcher
+                .events
+                .error(format!("Failed to restore timers: {:#}", e));
+        }
+
+        let bookmarks = match &self.bookmark_file {
+            Some(path) => Bookmarks::load(path).unwrap_or_else(|e| {
+                eprintln!("Failed to load bookmarks: {:#}", e);
+                self.matcher
+                    .events
+                    .error(format!("Failed to load bookmarks: {:#}", e));
+                Bookmarks::default()
+            }),
+            None => Bookmarks::default(),
+        };
+
+        let announcer = Announcer::start(
+            self.tts_engine.clone(),
+            self.combine_window,
+            self.matcher.events.clone(),
+        );
+        let mut tailers = Vec::new();
+        let result = tokio::select! {
+            result = self.follow_logs(&announcer, &mut tailers, &bookmarks) => result,
+            _ = shutdown.cancelled() => Ok(()),
+        };
+
+        drop(announcer);
+        if self.keep_timers_on_stop {
+            self.timers.suspend_all().await;
+        } else {
+            self.timers.cancel_all();
+            self.timers.close_state_file().await;
+        }
+        if let Some(path) = &self.bookmark_file {
+            self.save_bookmarks(path, bookmarks, &mut tailers).await;
+        }
+
+        match &result {
+            Ok(()) => println!("Monitoring stopped"),
+            Err(e) => self
+                .matcher
+                .events
+                .error(format!("Monitoring stopped: {:#}", e)),
+        }
+        result
+    }
+
+    async fn follow_logs(
+        &self,
+        announcer: &Announcer,
+        tailers: &mut Vec<LogTailer>,
+        bookmarks: &Bookmarks,
+    ) -> Result<()> {
+        // Logs found by the first scan start at `start_position`; later ones are new
+        let mut starting = true;
+        let mut last_scan: Option<Instant> = None;
+        let mut watcher = LogWatcher::new(&self.game_directory);
+
         loop {
-            // Find the most recent log file
-            let log_path = match find_most_recent_log(&self.game_directory)? {
-                Some(path) => path,
-                None => {
+            // Periodically check which log files should be followed
+            if last_scan.is_none_or(|scanned| scanned.elapsed() >= MTIME_CHECK_INTERVAL) {
+                last_scan = Some(Instant::now());
+                let log_paths = self.select_logs()?;
+
+                if log_paths.is_empty() {
                     println!("No eqlog_* files found, waiting...");
-                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    if watcher.wait_for_logs_changed(Duration::from_secs(1)).await
+                        == Wake::LogsChanged
+                    {
+                        // Pick up the new logs right away
+                        last_scan = None;
+                    }
                     continue;
                 }
-            };
-
-            println!("Monitoring: {:?}", log_path);
 
-            // Open and seek to end
-            let file = tokio::fs::File::open(&log_path)
-                .await
-                .context(format!("Failed to open: {}", log_path.display()))?;
-            let mut reader = BufReader::new(file);
-            reader
-                .seek(SeekFrom::End(0))
-                .await
-                .context("Failed to seek to end of log file")?;
-
-            // Monitor this file until a different file becomes most recent
-            let mut last_mtime_check = std::time::Instant::now();
-            let mut line_buffer = String::new();
-
-            loop {
-                match self.process_one_batch(&mut reader, &mut line_buffer).await? {
-                    Some(batch_result) => {
-                        // Spawn announcement tasks for immediate messages
-                        for announcement in batch_result.immediate {
-                            let engine = self.tts_engine.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = engine.announce(&announcement).await {
-                                    eprintln!("Failed to announce message: {}", e);
-                                }
-                            });
-                        }
+                // Deleted logs get a grace period to reappear (e.g. cleared by the player)
+                tailers.retain(|tailer| {
+                    let keep = log_paths.contains(&tailer.path)
+                        || tailer
+                            .missing_since
+                            .is_some_and(|since| since.elapsed() < MISSING_LOG_GRACE);
+                    if !keep {
+                        println!("Stopped monitoring: {:?}", tailer.path);
+                        self.matcher.events.publish(MonitorEvent::LogClosed {
+                            path: tailer.path.clone(),
+                            character: tailer.character.clone(),
+                        });
+                    }
+                    keep
+                });
+                let position = if starting {
+                    self.start_position
+                } else {
+                    StartPosition::End
+                };
+                starting = false;
+                for log_path in log_paths {
+                    if !tailers.iter().any(|tailer| tailer.path == log_path) {
+                        tailers.push(self.open_tailer(log_path, position, bookmarks).await?);
+                    }
+                }
+            }
 
-                        // Schedule timed delay announcements
-                        for (pattern, (announcement, delay_seconds)) in batch_result.timed_delay {
-                            // Use pattern as key for debouncing
-                            self.schedule_timed_delay(pattern, announcement, delay_seconds);
-                        }
+            let mut read_any = false;
+            for tailer in tailers.iter_mut() {
+                let batch = self
+                    .process_one_batch(
+                        &tailer.character,
+                        &mut tailer.reader,
+                        &mut tailer.line_buffer,
+                    )
+                    .await?;
+
+                match batch {
+                    Some(mut batch_result) => {
+                        read_any = true;
+                        self.drop_stale(batch_result.anchor_timers());
+                        self.dispatch(
+                            announcer,
+                            &tailer.character,
+                            batch_result,
+                            ReplaySpeed::REALTIME,
+                        );
                     }
+                    // At EOF, make sure the log wasn't truncated, replaced or deleted
                     None => {
-                        // EOF reached - check if we should switch files
-                        if last_mtime_check.elapsed() >= MTIME_CHECK_INTERVAL {
-                            last_mtime_check = std::time::Instant::now();
-                            if let Some(new_path) = find_most_recent_log(&self.game_directory)?
-                                && new_path != log_path {
-                                    println!("Switching to: {:?}", new_path);
-                                    break; // Break inner loop to reopen with new file
-                                }
+                        if tailer.check_file().await? {
+                            self.matcher.events.publish(MonitorEvent::LogReset {
+                                path: tailer.path.clone(),
+                                character: tailer.character.clone(),
+                            });
                         }
-                        tokio::time::sleep(IDLE_RETRY_DELAY).await;
                     }
                 }
             }
+
+            if !read_any {
+                // EOF reached on every log: wait for a write, or the next scan at the latest
+                let until_scan = last_scan.map_or(Duration::ZERO, |scanned| {
+                    MTIME_CHECK_INTERVAL.saturating_sub(scanned.elapsed())
+                });
+                if watcher.wait(until_scan).await == Wake::LogsChanged {
+                    // Pick up new or removed logs right away
+                    last_scan = None;
+                }
+            }
+        }
+    }
+
+    /// Replays a recorded log through the same matching and timer logic as live monitoring,
+    /// pacing lines by their original timestamps
+    /// Returns once the log (or time range) is exhausted and every announcement has played,
+    /// or as soon as `shutdown` is cancelled, with a summary of what was replayed until then
+    pub async fn replay(
+        &self,
+        log_path: &Path,
+        options: &ReplayOptions,
+        shutdown: CancellationToken,
+    ) -> Result<ReplaySummary> {
+        let character = tailer::character_from_log_path(log_path);
+
+        let file = tokio::fs::File::open(log_path)
+            .await
+            .context(format!("Failed to open: {}", log_path.display()))?;
+        let reader = tokio::io::BufReader::new(file);
+
+        let announcer = Announcer::start(
+            self.tts_engine.clone(),
+            self.combine_window,
+            self.matcher.events.clone(),
+        );
+        let mut summary = ReplaySummary::default();
+        let played = async {
+            self.replay_lines(&character, reader, options, &announcer, &mut summary)
+                .await?;
+            // Let pending timers and queued announcements play out
+            self.timers.wait_all().await;
+            announcer.finish().await;
+            anyhow::Ok(())
+        };
+        let stopped = tokio::select! {
+            result = played => result.map(|()| false),
+            _ = shutdown.cancelled() => Ok(true),
+        };
+        // Dropping an unfinished replay stopped queued announcements; stop its timers too
+        self.timers.cancel_all();
+        summary.stopped = stopped?;
+        Ok(summary)
+    }
+
+    /// Feeds a recorded log's lines within the replay range to the matcher, paced by speed
+    async fn replay_lines(
+        &self,
+        character: &str,
+        mut reader: tokio::io::BufReader<tokio::fs::File>,
+        options: &ReplayOptions,
+        announcer: &Announcer,
+        summary: &mut ReplaySummary,
+    ) -> Result<()> {
+        let mut line_buffer = Vec::new();
+        let mut pacer = Pacer::new(options.speed);
+        let mut last_timestamp = None;
+
+        loop {
+            line_buffer.clear();
+            let bytes_read = reader
+                .read_until(b'\n', &mut line_buffer)
+                .await
+                .context("Failed to read line from log file")?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            let line = LogLine::decode(&line_buffer, self.encoding);
+            last_timestamp = line.timestamp.or(last_timestamp);
+            match options.position(last_timestamp) {
+                RangePosition::Before => {
+                    // Still follow zone changes so zone-scoped triggers are right at the start
+                    self.matcher.track_zone(character, &line);
+                    continue;
+                }
+                RangePosition::After => break,
+                RangePosition::Within => {}
+            }
+
+            if let Some(timestamp) = line.timestamp {
+                tokio::time::sleep(pacer.delay_before(timestamp)).await;
+            }
+
+            let mut batch = BatchResult::default();
+            self.matcher.collect_matches(character, &line, &mut batch);
+            summary.lines += 1;
+            summary.matches += batch.matches.len();
+            self.dispatch(announcer, character, batch, options.speed);
+        }
+        Ok(())
+    }
+
+    /// Queues a batch's immediate announcements and schedules its timers
+    /// Timer delays are scaled by `timer_speed` (realtime when monitoring live)
+    fn dispatch(
+        &self,
+        announcer: &Announcer,
+        character: &str,
+        batch: BatchResult,
+        timer_speed: ReplaySpeed,
+    ) {
+        // Queue immediate messages (spoken separately or combined)
+        for announcement in batch.immediate {
+            announcer.announce(announcement);
+        }
+
+        // Schedule timed delay announcements
+        for (trigger, timed) in batch.timed_delay {
+            // Use character and trigger as key for debouncing
+            self.timers.schedule(
+                (character.to_string(), trigger),
+                timed.pattern,
+                timed.announcement,
+                timer_speed.scale(Duration::from_secs(timed.delay_seconds)),
+            );
+        }
+    }
+
+    /// Returns the log files that should currently be followed
+    fn select_logs(&self) -> Result<Vec<PathBuf>> {
+        match self.all_characters_window {
+            Some(window) => find_active_logs(&self.game_directory, window),
+            None => Ok(find_most_recent_log(&self.game_directory)?
+                .into_iter()
+                .collect()),
+        }
+    }
+
+    /// Opens a log file at its start position and guesses its character's zone from the
+    /// lines before it
+    async fn open_tailer(
+        &self,
+        log_path: PathBuf,
+        position: StartPosition,
+        bookmarks: &Bookmarks,
+    ) -> Result<LogTailer> {
+        println!("Monitoring: {:?}", log_path);
+        let len = tokio::fs::metadata(&log_path)
+            .await
+            .context(format!("Failed to read metadata: {}", log_path.display()))?
+            .len();
+        let offset = start::start_offset(&log_path, len, position, bookmarks, self.encoding)?;
+        if offset < len {
+            println!("Catching up on the last {} bytes", len - offset);
+        }
+        let tailer = LogTailer::open(log_path, offset).await?;
+        self.matcher.events.publish(MonitorEvent::LogOpened {
+            path: tailer.path.clone(),
+            character: tailer.character.clone(),
+        });
+
+        // Guess the zone from history, since we only read lines from the start position on
+        let zone = zone::guess_zone_from_history(&tailer.path, offset, self.encoding)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to guess zone from log history: {}", e);
+                self.matcher
+                    .events
+                    .error(format!("Failed to guess zone from log history: {:#}", e));
+                None
+            });
+        println!(
+            "Current zone of {}: {}",
+            tailer.character,
+            zone.as_deref().unwrap_or("unknown")
+        );
+        self.matcher.zone.set(&tailer.character, zone);
+
+        Ok(tailer)
+    }
+
+    /// Records where reading stopped in each followed log, so the next start can resume there
+    async fn save_bookmarks(
+        &self,
+        path: &Path,
+        mut bookmarks: Bookmarks,
+        tailers: &mut [LogTailer],
+    ) {
+        for tailer in tailers {
+            match tailer.position().await {
+                Ok(offset) => bookmarks.set(tailer.path.clone(), offset),
+                Err(e) => eprintln!("Failed to bookmark log: {:#}", e),
+            }
+        }
+        if let Err(e) = bookmarks.save(path) {
+            eprintln!("Failed to save bookmarks: {:#}", e);
+            self.matcher
+                .events
+                .error(format!("Failed to save bookmarks: {:#}", e));
         }
     }
 
     /// Processes one batch of log lines, collecting unique announcements
+    /// A line the game has only written part of is kept in `line_buffer` for the next batch
     ///
     /// Returns:
-    /// - `Ok(None)` if EOF is reached immediately (caller should sleep and retry)
+    /// - `Ok(None)` if no whole line is available yet (caller should sleep and retry)
     /// - `Ok(Some(BatchResult))` if data was read (categorized by message type)
     /// - `Err` on read errors
     async fn process_one_batch<R>(
         &self,
+        character: &str,
         reader: &mut R,
-        line_buffer: &mut String,
+        line_buffer: &mut LineBuffer,
     ) -> Result<Option<BatchResult>>
     where
-        R: AsyncBufReadExt + Unpin,
+        R: AsyncBufRead + Unpin,
     {
-        line_buffer.clear();
-
         // Try to read the first line
-        let bytes_read = reader
-            .read_line(line_buffer)
+        let Some(line) = line_buffer
+            .read_line(reader)
             .await
-            .context("Failed to read line from log file")?;
-
-        if bytes_read == 0 {
+            .context("Failed to read line from log file")?
+        else {
             // EOF reached - signal caller to sleep
             return Ok(None);
-        }
+        };
 
         // We got at least one line - start batch collection
-        // Use HashSet for deduplication of immediate announcements
-        let mut immediate_set = HashSet::new();
-        let mut timed_delay = HashMap::new();
+        let mut batch = BatchResult::default();
 
         // Check if this first line matches any configured messages
-        for config in self.match_message(line_buffer) {
-            println!(
-                "Match found! Log: '{}' -> Announcing: '{}'",
-                line_buffer.trim(),
-                config.announcement()
-            );
-            match config {
-                MessageConfig::Simple { announcement, .. } => {
-                    immediate_set.insert(announcement.clone());
-                }
-                MessageConfig::TimedDelay {
-                    pattern,
-                    announcement,
-                    timer_delay_in_seconds,
-                } => {
-                    timed_delay.insert(
-                        pattern.clone(),
-                        (announcement.clone(), *timer_delay_in_seconds),
-                    );
-                }
-            }
-        }
+        self.collect_line(character, line, &mut batch);
 
         // Try to read more lines with timeout to batch collect immediately available data
         loop {
-            line_buffer.clear();
-
             // Use timeout to check if more data is immediately available
-            match tokio::time::timeout(BATCH_READ_TIMEOUT, reader.read_line(line_buffer)).await {
-                Ok(Ok(bytes)) if bytes > 0 => {
+            let next_line = line_buffer.read_line(reader);
+            match tokio::time::timeout(BATCH_READ_TIMEOUT, next_line).await {
+                Ok(Ok(Some(line))) => {
                     // Got another line - check for matches
-                    for config in self.match_message(line_buffer) {
-                        println!(
-                            "Match found! Log: '{}' -> Announcing: '{}'",
-                            line_buffer.trim(),
-                            config.announcement()
-                        );
-                        match config {
-                            MessageConfig::Simple { announcement, .. } => {
-                                immediate_set.insert(announcement.clone());
-                            }
-                            MessageConfig::TimedDelay {
-                                pattern,
-                                announcement,
-                                timer_delay_in_seconds,
-                            } => {
-                                timed_delay.insert(
-                                    pattern.clone(),
-                                    (announcement.clone(), *timer_delay_in_seconds),
-                                );
-                            }
-                        }
-                    }
+                    self.collect_line(character, line, &mut batch);
                 }
-                Ok(Ok(_)) => {
+                Ok(Ok(None)) => {
                     // EOF reached - stop batching
                     break;
                 }
@@ -251,75 +767,105 @@ impl LogMonitor {
             }
         }
 
-        Ok(Some(BatchResult {
-            immediate: immediate_set.into_iter().collect(),
-            timed_delay,
-        }))
+        Ok(Some(batch))
     }
 
-    /// Schedules a timed delay announcement
-    /// If a timer already exists for this pattern, it will be cancelled and replaced (debounce behavior)
-    fn schedule_timed_delay(&self, pattern: String, announcement: String, delay_seconds: u64) {
-        let timers = Arc::clone(&self.active_timers);
-        let engine = self.tts_engine.clone();
-
-        // Cancel existing timer for this pattern if present
-        {
-            let mut timers_map = timers.lock().unwrap();
-            if let Some(old_handle) = timers_map.remove(&pattern) {
-                old_handle.abort();
-                println!("Cancelled existing timer for pattern: '{}'", pattern);
-            }
+    /// Adds a live log line's matches to the batch, recording how late the line was read
+    /// Stale lines are still matched, but their announcements and timers may be dropped
+    fn collect_line(&self, character: &str, raw: &[u8], batch: &mut BatchResult) {
+        let line = LogLine::decode(raw, self.encoding);
+        let lag = line.timestamp.map(lag::line_lag);
+        let stale = lag
+            .zip(self.max_line_lag)
+            .is_some_and(|(lag, max_lag)| lag > max_lag);
+        if let Some(lag) = lag {
+            self.lag.record(lag, stale);
         }
 
-        // Clone for logging before moving into async block
-        let pattern_clone = pattern.clone();
-        let announcement_clone = announcement.clone();
-
-        // Start new timer
-        let handle = tokio::spawn(async move {
-            tokio::time::sleep(Duration::from_secs(delay_seconds)).await;
-            if let Err(e) = engine.announce(&announcement).await {
-                eprintln!("Failed to announce timed message: {}", e);
-            }
-        });
-
-        // Store the new timer handle
-        {
-            let mut timers_map = timers.lock().unwrap();
-            timers_map.insert(pattern, handle);
+        if !stale {
+            self.matcher.collect_matches(character, &line, batch);
+            return;
         }
 
-        println!(
-            "Scheduled timer: '{}' -> '{}' ({}s)",
-            pattern_clone, announcement_clone, delay_seconds
-        );
+        // Old news isn't announced; timers may still have time left
+        let mut stale_batch = BatchResult::default();
+        self.matcher
+            .collect_matches(character, &line, &mut stale_batch);
+        let mut dropped = stale_batch.immediate;
+        match self.stale_lines {
+            StaleLinePolicy::Suppress => dropped.extend(
+                stale_batch
+                    .timed_delay
+                    .into_values()
+                    .map(|timed| timed.announcement),
+            ),
+            StaleLinePolicy::Shorten => batch.timed_delay.extend(stale_batch.timed_delay),
+        }
+        self.drop_stale(dropped);
+        batch.matches.extend(stale_batch.matches);
     }
 
-    /// Checks if a log line matches any configured messages
-    /// Returns all matching MessageConfigs (supports same pattern with different types)
-    fn match_message(&self, line: &str) -> Vec<&MessageConfig> {
-        self.messages
-            .iter()
-            .filter(|message_config| line.contains(message_config.pattern()))
-            .collect()
+    /// Reports announcements dropped because their line was read too late
+    fn drop_stale(&self, announcements: Vec<Announcement>) {
+        for announcement in announcements {
+            println!("Suppressed stale announcement: '{}'", announcement.text);
+            self.matcher
+                .events
+                .publish(MonitorEvent::AnnouncementDropped {
+                    text: announcement.text,
+                    reason: DropReason::Stale,
+                });
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quarm_config::{ChatChannel, EventCondition, EventKind, TriggerOptions, ZoneFilter};
+    use std::io::Write;
     use tokio::io::BufReader;
 
+    // Character the test logs are read for
+    const TEST_CHARACTER: &str = "Soandso";
+
+    // Helper function to list the texts of a batch's immediate announcements
+    fn immediate_texts(batch: &BatchResult) -> Vec<&str> {
+        batch.immediate.iter().map(|a| a.text.as_str()).collect()
+    }
+
+    // Log line with a timestamp some seconds in the past
+    fn written_ago(seconds: i64, body: &str) -> String {
+        let written = chrono::Local::now() - chrono::TimeDelta::seconds(seconds);
+        format!("{} {}\n", written.format("[%a %b %d %H:%M:%S %Y]"), body)
+    }
+
     // Helper function to create a test LogMonitor with custom message configs
     fn create_test_monitor(messages: Vec<MessageConfig>) -> LogMonitor {
+        let matcher = TriggerMatcher::new(Config {
+            messages,
+            ..Config::default()
+        })
+        .expect("Invalid test triggers");
+        // Create a mock TtsEngine - it won't be used in process_one_batch tests
+        // but is required for struct construction
+        let tts_engine = TtsEngine::new_mock().expect("Failed to create mock TTS engine");
         LogMonitor {
             game_directory: PathBuf::from("/test/game"),
-            messages,
-            // Create a mock TtsEngine - it won't be used in process_one_batch tests
-            // but is required for struct construction
-            tts_engine: TtsEngine::new_mock().expect("Failed to create mock TTS engine"),
-            active_timers: Arc::new(Mutex::new(HashMap::new())),
+            timers: Timers::new(tts_engine.clone(), matcher.events.clone()),
+            matcher,
+            tts_engine,
+            combine_window: None,
+            all_characters_window: None,
+            timer_state_file: None,
+            late_timers: LateTimerPolicy::Drop,
+            keep_timers_on_stop: false,
+            max_line_lag: None,
+            stale_lines: StaleLinePolicy::Suppress,
+            lag: LagTracker::default(),
+            start_position: StartPosition::End,
+            bookmark_file: None,
+            encoding: encoding_rs::WINDOWS_1252,
         }
     }
 
@@ -329,17 +875,18 @@ mod tests {
         let messages = vec![MessageConfig::Simple {
             pattern: "charm spell has worn off".to_string(),
             announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
 
         let log_data = "Your charm spell has worn off.\n".repeat(5);
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -347,7 +894,7 @@ mod tests {
         assert!(result.is_some());
         let batch = result.unwrap();
         assert_eq!(batch.immediate.len(), 1);
-        assert!(batch.immediate.contains(&"charm break".to_string()));
+        assert!(immediate_texts(&batch).contains(&"charm break"));
         assert_eq!(batch.timed_delay.len(), 0);
     }
 
@@ -358,10 +905,12 @@ mod tests {
             MessageConfig::Simple {
                 pattern: "charm spell has worn off".to_string(),
                 announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
             },
             MessageConfig::Simple {
                 pattern: "Root spell has worn off".to_string(),
                 announcement: "root break".to_string(),
+                options: TriggerOptions::default(),
             },
         ];
 
@@ -372,11 +921,11 @@ mod tests {
                        Your charm spell has worn off.\n\
                        Your charm spell has worn off.\n";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -384,8 +933,8 @@ mod tests {
         assert!(result.is_some());
         let batch = result.unwrap();
         assert_eq!(batch.immediate.len(), 2);
-        assert!(batch.immediate.contains(&"charm break".to_string()));
-        assert!(batch.immediate.contains(&"root break".to_string()));
+        assert!(immediate_texts(&batch).contains(&"charm break"));
+        assert!(immediate_texts(&batch).contains(&"root break"));
         assert_eq!(batch.timed_delay.len(), 0);
     }
 
@@ -395,17 +944,18 @@ mod tests {
         let messages = vec![MessageConfig::Simple {
             pattern: "charm spell has worn off".to_string(),
             announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
 
         let log_data = "Your charm spell has worn off.\n";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -413,7 +963,7 @@ mod tests {
         assert!(result.is_some());
         let batch = result.unwrap();
         assert_eq!(batch.immediate.len(), 1);
-        assert!(batch.immediate.contains(&"charm break".to_string()));
+        assert!(immediate_texts(&batch).contains(&"charm break"));
         assert_eq!(batch.timed_delay.len(), 0);
     }
 
@@ -423,6 +973,7 @@ mod tests {
         let messages = vec![MessageConfig::Simple {
             pattern: "charm spell has worn off".to_string(),
             announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
@@ -430,11 +981,11 @@ mod tests {
         let log_data = "Some random log message.\n\
                        Another unrelated message.\n";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -451,17 +1002,18 @@ mod tests {
         let messages = vec![MessageConfig::Simple {
             pattern: "charm spell has worn off".to_string(),
             announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
 
         let log_data = "";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -476,10 +1028,12 @@ mod tests {
             MessageConfig::Simple {
                 pattern: "charm spell has worn off".to_string(),
                 announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
             },
             MessageConfig::Simple {
                 pattern: "snare".to_string(),
                 announcement: "snare faded".to_string(),
+                options: TriggerOptions::default(),
             },
         ];
 
@@ -492,11 +1046,11 @@ mod tests {
                        Another random message.\n\
                        Your charm spell has worn off.\n";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -504,28 +1058,89 @@ mod tests {
         assert!(result.is_some());
         let batch = result.unwrap();
         assert_eq!(batch.immediate.len(), 2);
-        assert!(batch.immediate.contains(&"charm break".to_string()));
-        assert!(batch.immediate.contains(&"snare faded".to_string()));
+        assert!(immediate_texts(&batch).contains(&"charm break"));
+        assert!(immediate_texts(&batch).contains(&"snare faded"));
         assert_eq!(batch.timed_delay.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_non_utf8_lines() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "Brûlé tells you".to_string(),
+                announcement: "tell from Brûlé".to_string(),
+                options: TriggerOptions::default(),
+            },
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            },
+        ];
+        let monitor = create_test_monitor(messages);
+
+        // Windows-1252 text, then a line of bytes that aren't valid UTF-8
+        let log_data: &[u8] = b"[Fri Oct 16 12:00:00 2026] Br\xfbl\xe9 tells you, 'hi'\n\
+            [Fri Oct 16 12:00:01 2026] \xff\xfe\xc3 garbled\n\
+            [Fri Oct 16 12:00:02 2026] Your charm spell has worn off.\n";
+        let mut reader = BufReader::new(log_data);
+        let mut line_buffer = LineBuffer::default();
+
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            immediate_texts(&batch),
+            vec!["tell from Brûlé", "charm break"]
+        );
+        assert_eq!(batch.matches[0].body, "Brûlé tells you, 'hi'");
+
+        // Read as UTF-8, the bad bytes are replaced and the rest of the log still matches
+        let monitor = LogMonitor {
+            encoding: encoding_rs::UTF_8,
+            ..create_test_monitor(vec![MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            }])
+        };
+        let mut reader = BufReader::new(log_data);
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(immediate_texts(&batch), vec!["charm break"]);
+    }
+
     #[test]
     fn test_match_message() {
         // Test the match_message helper
         let messages = vec![MessageConfig::Simple {
             pattern: "charm spell has worn off".to_string(),
             announcement: "charm break".to_string(),
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
 
         // Should match
-        let result = monitor.match_message("Your charm spell has worn off.");
+        let result = monitor
+            .matcher
+            .match_message(TEST_CHARACTER, "Your charm spell has worn off.");
         assert_eq!(result.len(), 1);
-        assert_eq!(result[0].announcement(), "charm break");
+        assert_eq!(result[0].0, 0);
+        assert_eq!(result[0].1.announcement(), "charm break");
 
         // Should not match
-        assert!(monitor.match_message("Some other message").is_empty());
+        assert!(
+            monitor
+                .matcher
+                .match_message(TEST_CHARACTER, "Some other message")
+                .is_empty()
+        );
     }
 
     #[test]
@@ -547,17 +1162,18 @@ mod tests {
             pattern: "Charm spell has taken hold".to_string(),
             announcement: "charm about to break".to_string(),
             timer_delay_in_seconds: 30,
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
 
         let log_data = "Your Charm spell has taken hold.\n";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -567,9 +1183,9 @@ mod tests {
         assert_eq!(batch.immediate.len(), 0);
         assert_eq!(batch.timed_delay.len(), 1);
 
-        let (announcement, delay) = batch.timed_delay.get("Charm spell has taken hold").unwrap();
-        assert_eq!(announcement, "charm about to break");
-        assert_eq!(*delay, 30);
+        let timed = &batch.timed_delay[&0];
+        assert_eq!(timed.announcement.text, "charm about to break");
+        assert_eq!(timed.delay_seconds, 30);
     }
 
     #[tokio::test]
@@ -579,11 +1195,13 @@ mod tests {
             MessageConfig::Simple {
                 pattern: "charm spell has worn off".to_string(),
                 announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
             },
             MessageConfig::TimedDelay {
                 pattern: "Charm spell has taken hold".to_string(),
                 announcement: "charm about to break".to_string(),
                 timer_delay_in_seconds: 30,
+                options: TriggerOptions::default(),
             },
         ];
 
@@ -593,11 +1211,11 @@ mod tests {
                        Your Charm spell has taken hold.\n\
                        Your charm spell has worn off.\n";
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -607,13 +1225,13 @@ mod tests {
 
         // Should have 1 unique immediate (deduplicated charm break)
         assert_eq!(batch.immediate.len(), 1);
-        assert!(batch.immediate.contains(&"charm break".to_string()));
+        assert!(immediate_texts(&batch).contains(&"charm break"));
 
         // Should have 1 timed_delay
         assert_eq!(batch.timed_delay.len(), 1);
-        let (announcement, delay) = batch.timed_delay.get("Charm spell has taken hold").unwrap();
-        assert_eq!(announcement, "charm about to break");
-        assert_eq!(*delay, 30);
+        let timed = &batch.timed_delay[&1];
+        assert_eq!(timed.announcement.text, "charm about to break");
+        assert_eq!(timed.delay_seconds, 30);
     }
 
     #[tokio::test]
@@ -623,6 +1241,7 @@ mod tests {
             pattern: "Charm spell has taken hold".to_string(),
             announcement: "charm about to break".to_string(),
             timer_delay_in_seconds: 30,
+            options: TriggerOptions::default(),
         }];
 
         let monitor = create_test_monitor(messages);
@@ -630,11 +1249,11 @@ mod tests {
         // Multiple instances of the same timed delay message
         let log_data = "Your Charm spell has taken hold.\n".repeat(3);
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -645,9 +1264,9 @@ mod tests {
         assert_eq!(batch.timed_delay.len(), 1);
 
         // Verify the content
-        let (announcement, delay) = batch.timed_delay.get("Charm spell has taken hold").unwrap();
-        assert_eq!(announcement, "charm about to break");
-        assert_eq!(*delay, 30);
+        let timed = &batch.timed_delay[&0];
+        assert_eq!(timed.announcement.text, "charm about to break");
+        assert_eq!(timed.delay_seconds, 30);
     }
 
     #[tokio::test]
@@ -658,11 +1277,13 @@ mod tests {
             MessageConfig::Simple {
                 pattern: "flesh begins to liquefy".to_string(),
                 announcement: "go back in".to_string(),
+                options: TriggerOptions::default(),
             },
             MessageConfig::TimedDelay {
                 pattern: "flesh begins to liquefy".to_string(),
                 announcement: "get out".to_string(),
                 timer_delay_in_seconds: 22,
+                options: TriggerOptions::default(),
             },
         ];
 
@@ -671,11 +1292,11 @@ mod tests {
         // 3 identical log lines matching the same pattern
         let log_data = "Your flesh begins to liquefy.\n".repeat(3);
         let mut reader = BufReader::new(log_data.as_bytes());
-        let mut line_buffer = String::new();
+        let mut line_buffer = LineBuffer::default();
 
         // Act
         let result = monitor
-            .process_one_batch(&mut reader, &mut line_buffer)
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
             .await
             .unwrap();
 
@@ -685,15 +1306,772 @@ mod tests {
 
         // 1 immediate announcement (deduplicated from 3 lines)
         assert_eq!(batch.immediate.len(), 1);
-        assert!(batch.immediate.contains(&"go back in".to_string()));
+        assert!(immediate_texts(&batch).contains(&"go back in"));
 
         // 1 timed delay entry (deduplicated from 3 lines)
         assert_eq!(batch.timed_delay.len(), 1);
-        let (announcement, delay) = batch
-            .timed_delay
-            .get("flesh begins to liquefy")
+        let timed = &batch.timed_delay[&1];
+        assert_eq!(timed.pattern, "flesh begins to liquefy");
+        assert_eq!(timed.announcement.text, "get out");
+        assert_eq!(timed.delay_seconds, 22);
+    }
+
+    #[tokio::test]
+    async fn test_matches_body_and_keeps_timestamp() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            },
+            // Would match every line if patterns saw the timestamp prefix
+            MessageConfig::Simple {
+                pattern: "2026]".to_string(),
+                announcement: "timestamp leak".to_string(),
+                options: TriggerOptions::default(),
+            },
+        ];
+
+        let monitor = create_test_monitor(messages);
+
+        let log_data = "[Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n\
+                        [Fri Oct 16 12:00:05 2026] You feel less charming.\n\
+                        Your charm spell has worn off.\n";
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let mut line_buffer = LineBuffer::default();
+
+        // Act
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Assert: Only the charm lines match, each match carries its own timestamp
+        assert_eq!(immediate_texts(&batch), vec!["charm break"]);
+        assert_eq!(batch.matches.len(), 2);
+
+        let first = &batch.matches[0];
+        assert_eq!(
+            first.timestamp,
+            Some(
+                chrono::NaiveDate::from_ymd_opt(2026, 10, 16)
+                    .unwrap()
+                    .and_hms_opt(12, 0, 0)
+                    .unwrap()
+            )
+        );
+        assert_eq!(first.body, "Your charm spell has worn off.");
+        assert_eq!(first.pattern, "charm spell has worn off");
+        assert_eq!(first.announcement, "charm break");
+
+        // Lines without a timestamp still match, with no timestamp attached
+        assert_eq!(batch.matches[1].timestamp, None);
+    }
+
+    #[tokio::test]
+    async fn test_event_triggers() {
+        let mut from_guild = EventCondition::kind(EventKind::Chat);
+        from_guild.channel = Some(ChatChannel::Guild);
+        let mut big_hit = EventCondition::kind(EventKind::Damage);
+        big_hit.target = Some("YOU".to_string());
+        big_hit.min_amount = Some(100);
+
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: String::new(),
+                announcement: "tell".to_string(),
+                options: TriggerOptions {
+                    event: Some(EventCondition::kind(EventKind::Tell)),
+                    ..TriggerOptions::default()
+                },
+            },
+            // Pattern and event condition must both match
+            MessageConfig::Simple {
+                pattern: "raid".to_string(),
+                announcement: "guild raid".to_string(),
+                options: TriggerOptions {
+                    event: Some(from_guild),
+                    ..TriggerOptions::default()
+                },
+            },
+            MessageConfig::Simple {
+                pattern: String::new(),
+                announcement: "big hit".to_string(),
+                options: TriggerOptions {
+                    event: Some(big_hit),
+                    ..TriggerOptions::default()
+                },
+            },
+        ];
+
+        let monitor = create_test_monitor(messages);
+
+        let log_data = "[Fri Oct 16 12:00:00 2026] Soandso tells you, 'hi'\n\
+                        [Fri Oct 16 12:00:01 2026] Soandso tells the raid, 'raid time'\n\
+                        [Fri Oct 16 12:00:02 2026] Soandso tells the guild, 'raid time'\n\
+                        [Fri Oct 16 12:00:03 2026] a fire beetle bites YOU for 12 points of damage.\n\
+                        [Fri Oct 16 12:00:04 2026] Lord Nagafen hits YOU for 250 points of damage.\n";
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let mut line_buffer = LineBuffer::default();
+
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            immediate_texts(&batch),
+            vec!["tell", "guild raid", "big hit"]
+        );
+        assert_eq!(batch.matches.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_zone_scoped_triggers() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "flesh begins to liquefy".to_string(),
+                announcement: "fear".to_string(),
+                options: TriggerOptions {
+                    zones: Some(ZoneFilter {
+                        include: vec!["The Plane of Fear".to_string()],
+                        exclude: Vec::new(),
+                    }),
+                    ..TriggerOptions::default()
+                },
+            },
+            MessageConfig::Simple {
+                pattern: "flesh begins to liquefy".to_string(),
+                announcement: "not hate".to_string(),
+                options: TriggerOptions {
+                    zones: Some(ZoneFilter {
+                        include: Vec::new(),
+                        exclude: vec!["The Plane of Hate".to_string()],
+                    }),
+                    ..TriggerOptions::default()
+                },
+            },
+        ];
+
+        let monitor = create_test_monitor(messages);
+        let zone = monitor.zone_tracker();
+        zone.set(TEST_CHARACTER, Some("The Plane of Hate".to_string()));
+
+        let log_data = "[Fri Oct 16 12:00:00 2026] Your flesh begins to liquefy.\n";
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let mut line_buffer = LineBuffer::default();
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(batch.immediate.is_empty());
+
+        // Zoning mid-batch applies to the lines after it
+        let log_data = "[Fri Oct 16 12:01:00 2026] You have entered The Plane of Fear.\n\
+                        [Fri Oct 16 12:02:00 2026] Your flesh begins to liquefy.\n";
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(immediate_texts(&batch), vec!["fear", "not hate"]);
+        assert_eq!(zone.current().as_deref(), Some("The Plane of Fear"));
+    }
+
+    #[tokio::test]
+    async fn test_character_scoped_triggers_and_prefix() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "Mesmerization spell has worn off".to_string(),
+                announcement: "mez break".to_string(),
+                options: TriggerOptions {
+                    characters: vec!["Otherguy".to_string()],
+                    ..TriggerOptions::default()
+                },
+            },
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            },
+        ];
+
+        let mut monitor = create_test_monitor(messages);
+        monitor.matcher.prefix_character_name = true;
+
+        let log_data = "[Fri Oct 16 12:00:00 2026] Your Mesmerization spell has worn off.\n\
+                        [Fri Oct 16 12:00:01 2026] Your charm spell has worn off.\n";
+        for (character, expected) in [
+            ("Soandso", vec!["Soandso, charm break"]),
+            (
+                "Otherguy",
+                vec!["Otherguy, mez break", "Otherguy, charm break"],
+            ),
+        ] {
+            let mut reader = BufReader::new(log_data.as_bytes());
+            let mut line_buffer = LineBuffer::default();
+            let batch = monitor
+                .process_one_batch(character, &mut reader, &mut line_buffer)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(immediate_texts(&batch), expected);
+            assert!(batch.matches.iter().all(|m| m.character == character));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timers_kept_apart_per_character() {
+        let monitor = create_test_monitor(Vec::new());
+        let announcement = Announcement {
+            text: "go back in".to_string(),
+            style: AnnouncementStyle::default(),
+        };
+
+        for character in ["Soandso", "Otherguy", "Soandso"] {
+            monitor.timers.schedule(
+                (character.to_string(), 0),
+                "flesh begins to liquefy".to_string(),
+                announcement.clone(),
+                Duration::from_secs(60),
+            );
+        }
+
+        // The second Soandso timer replaced the first; Otherguy's is untouched
+        let timers = monitor.timers().list();
+        assert_eq!(timers.len(), 2);
+        assert!(timers.iter().any(|timer| timer.character == "Otherguy"));
+        monitor.timers().cancel_all();
+    }
+
+    #[test]
+    fn test_find_active_logs() {
+        let dir = std::env::temp_dir().join(format!("quarm_active_logs_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let stale = dir.join("eqlog_Oldtimer_pq.proj.txt");
+        let recent = [
+            dir.join("eqlog_Otherguy_pq.proj.txt"),
+            dir.join("eqlog_Soandso_pq.proj.txt"),
+        ];
+        std::fs::write(&stale, "").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+        for path in &recent {
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let window = Duration::from_secs(600);
+        assert_eq!(find_active_logs(&dir, window).unwrap(), recent.to_vec());
+
+        // With nothing recent, the newest log is still followed
+        for path in &recent {
+            std::fs::remove_file(path).unwrap();
+        }
+        assert_eq!(find_active_logs(&dir, window).unwrap(), vec![stale]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_character_profiles() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            },
+            MessageConfig::Simple {
+                pattern: "Root spell has worn off".to_string(),
+                announcement: "root break".to_string(),
+                options: TriggerOptions::default(),
+            },
+        ];
+
+        let mut monitor = create_test_monitor(messages);
+        monitor.matcher.profiles.by_character.insert(
+            "Soandso".to_string(),
+            CharacterProfile {
+                triggers: Some(vec!["charm spell has worn off".to_string()]),
+                speaker: Some(2),
+                prefix: Some("enchanter".to_string()),
+            },
+        );
+
+        let log_data = "[Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n\
+                        [Fri Oct 16 12:00:01 2026] Your Root spell has worn off.\n";
+
+        // Soandso only has charm break enabled, spoken with their voice and prefix
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let mut line_buffer = LineBuffer::default();
+        let batch = monitor
+            .process_one_batch("Soandso", &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
             .unwrap();
-        assert_eq!(announcement, "get out");
-        assert_eq!(*delay, 22);
+        assert_eq!(immediate_texts(&batch), vec!["enchanter, charm break"]);
+        assert_eq!(batch.immediate[0].style.speaker, Some(2));
+
+        // Characters without a profile get every trigger in the default voice
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let batch = monitor
+            .process_one_batch("Otherguy", &mut reader, &mut line_buffer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(immediate_texts(&batch), vec!["charm break", "root break"]);
+        assert_eq!(batch.immediate[0].style.speaker, None);
+    }
+
+    #[test]
+    fn test_announcement_variants_include_profiles() {
+        let mut config = Config {
+            messages: vec![MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            }],
+            ..Config::default()
+        };
+        config.profiles.by_character.insert(
+            "Soandso".to_string(),
+            CharacterProfile {
+                speaker: Some(2),
+                ..CharacterProfile::default()
+            },
+        );
+
+        let variants = announcement_variants(&config);
+        let texts: Vec<&str> = variants.iter().map(|(text, _)| text.as_str()).collect();
+        assert_eq!(texts, vec!["charm break", "charm break"]);
+        assert_eq!(variants[1].1.speaker, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_replay_time_range() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions {
+                    zones: Some(ZoneFilter {
+                        include: vec!["The Plane of Fear".to_string()],
+                        exclude: Vec::new(),
+                    }),
+                    ..TriggerOptions::default()
+                },
+            },
+            MessageConfig::TimedDelay {
+                pattern: "flesh begins to liquefy".to_string(),
+                announcement: "go back in".to_string(),
+                timer_delay_in_seconds: 22,
+                options: TriggerOptions::default(),
+            },
+        ];
+        let monitor = create_test_monitor(messages);
+
+        let path =
+            std::env::temp_dir().join(format!("eqlog_Soandso_replay{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "[Fri Oct 16 11:00:00 2026] You have entered The Plane of Fear.\n\
+             [Fri Oct 16 11:59:00 2026] Your charm spell has worn off.\n\
+             [Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n\
+             a line without a timestamp\n\
+             [Fri Oct 16 12:05:00 2026] Your flesh begins to liquefy.\n\
+             [Fri Oct 16 13:00:00 2026] Your charm spell has worn off.\n",
+        )
+        .unwrap();
+
+        let options = ReplayOptions {
+            speed: ReplaySpeed::Instant,
+            start: Some(parse_replay_time("2026-10-16 12:00:00").unwrap()),
+            end: Some(parse_replay_time("2026-10-16 12:30:00").unwrap()),
+        };
+        let summary = tokio::time::timeout(
+            Duration::from_secs(30),
+            monitor.replay(&path, &options, CancellationToken::new()),
+        )
+        .await
+        .expect("instant replay should not wait on timers")
+        .unwrap();
+
+        // The zone line before the range still counts; the charm lines outside it don't
+        assert_eq!(
+            summary,
+            ReplaySummary {
+                lines: 3,
+                matches: 2,
+                stopped: false,
+            }
+        );
+        assert_eq!(
+            monitor.zone_tracker().for_character("Soandso").as_deref(),
+            Some("The Plane of Fear")
+        );
+        assert!(monitor.timers().list().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_publishes_events() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            },
+            MessageConfig::TimedDelay {
+                pattern: "flesh begins to liquefy".to_string(),
+                announcement: "go back in".to_string(),
+                timer_delay_in_seconds: 22,
+                options: TriggerOptions::default(),
+            },
+        ];
+        let monitor = create_test_monitor(messages);
+        let mut subscriber = monitor.events().subscribe();
+
+        let path =
+            std::env::temp_dir().join(format!("eqlog_Soandso_events{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "[Fri Oct 16 12:00:00 2026] You have entered The Plane of Fear.\n\
+             [Fri Oct 16 12:00:01 2026] Your charm spell has worn off.\n\
+             [Fri Oct 16 12:00:02 2026] Your flesh begins to liquefy.\n",
+        )
+        .unwrap();
+
+        let options = ReplayOptions {
+            speed: ReplaySpeed::Instant,
+            start: None,
+            end: None,
+        };
+        tokio::time::timeout(
+            Duration::from_secs(30),
+            monitor.replay(&path, &options, CancellationToken::new()),
+        )
+        .await
+        .expect("instant replay should not wait on timers")
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Playback finishes in its own tasks, so its outcome is checked apart
+        let mut events = Vec::new();
+        let mut spoken = Vec::new();
+        while let Ok(event) = subscriber.try_recv() {
+            match event {
+                MonitorEvent::AnnouncementPlayed { text } => spoken.push(text),
+                // The mock engine has no audio device to play on
+                MonitorEvent::Error { message } if message.starts_with("Failed to announce") => {
+                    spoken.push(message)
+                }
+                event => events.push(event),
+            }
+        }
+
+        assert_eq!(
+            events[0],
+            MonitorEvent::ZoneChanged {
+                character: "Soandso".to_string(),
+                zone: "The Plane of Fear".to_string(),
+            }
+        );
+        assert!(matches!(
+            &events[1],
+            MonitorEvent::LineMatched(m) if m.announcement == "charm break"
+        ));
+        assert_eq!(
+            events[2],
+            MonitorEvent::AnnouncementQueued {
+                text: "charm break".to_string()
+            }
+        );
+        assert!(matches!(&events[3], MonitorEvent::LineMatched(m) if m.trigger == 1));
+        assert!(matches!(
+            &events[4],
+            MonitorEvent::TimerScheduled { delay_secs, .. } if *delay_secs == 0.0
+        ));
+        assert!(matches!(
+            &events[5],
+            MonitorEvent::TimerFired { announcement, .. } if announcement == "go back in"
+        ));
+        assert_eq!(events.len(), 6);
+
+        assert_eq!(spoken.len(), 2);
+        assert!(spoken.iter().any(|text| text.contains("charm break")));
+        assert!(spoken.iter().any(|text| text.contains("go back in")));
+    }
+
+    #[tokio::test]
+    async fn test_timed_event_triggers_fire_apart() {
+        // Event-only triggers share an empty pattern but are still separate timers
+        let messages = vec![
+            MessageConfig::TimedDelay {
+                pattern: String::new(),
+                announcement: "reply".to_string(),
+                timer_delay_in_seconds: 30,
+                options: TriggerOptions {
+                    event: Some(EventCondition::kind(EventKind::Tell)),
+                    ..TriggerOptions::default()
+                },
+            },
+            MessageConfig::TimedDelay {
+                pattern: String::new(),
+                announcement: "heal up".to_string(),
+                timer_delay_in_seconds: 60,
+                options: TriggerOptions {
+                    event: Some(EventCondition::kind(EventKind::Damage)),
+                    ..TriggerOptions::default()
+                },
+            },
+        ];
+        let monitor = create_test_monitor(messages);
+        let mut subscriber = monitor.events().subscribe();
+
+        let path = std::env::temp_dir().join(format!(
+            "eqlog_Soandso_timed_events{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "[Fri Oct 16 12:00:00 2026] Soandso tells you, 'hi'\n\
+             [Fri Oct 16 12:00:01 2026] Lord Nagafen hits YOU for 250 points of damage.\n",
+        )
+        .unwrap();
+
+        let options = ReplayOptions {
+            speed: ReplaySpeed::Instant,
+            start: None,
+            end: None,
+        };
+        tokio::time::timeout(
+            Duration::from_secs(30),
+            monitor.replay(&path, &options, CancellationToken::new()),
+        )
+        .await
+        .expect("instant replay should not wait on timers")
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut fired: Vec<String> = std::iter::from_fn(|| subscriber.try_recv().ok())
+            .filter_map(|event| match event {
+                MonitorEvent::TimerFired { announcement, .. } => Some(announcement),
+                _ => None,
+            })
+            .collect();
+        fired.sort();
+        assert_eq!(fired, vec!["heal up", "reply"]);
+    }
+
+    #[tokio::test]
+    async fn test_stale_lines() {
+        let messages = vec![
+            MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            },
+            MessageConfig::TimedDelay {
+                pattern: "flesh begins to liquefy".to_string(),
+                announcement: "go back in".to_string(),
+                timer_delay_in_seconds: 60,
+                options: TriggerOptions::default(),
+            },
+        ];
+        let log_data = [
+            written_ago(120, "Your charm spell has worn off."),
+            written_ago(45, "Your flesh begins to liquefy."),
+            written_ago(0, "Your charm spell has worn off."),
+        ]
+        .concat();
+
+        // Suppressed: only the fresh line is announced
+        let monitor = LogMonitor {
+            max_line_lag: Some(Duration::from_secs(30)),
+            ..create_test_monitor(messages.clone())
+        };
+        let mut subscriber = monitor.events().subscribe();
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut LineBuffer::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(immediate_texts(&batch), vec!["charm break"]);
+        assert!(batch.timed_delay.is_empty());
+        assert_eq!(batch.matches.len(), 3);
+        let dropped: Vec<_> = std::iter::from_fn(|| subscriber.try_recv().ok())
+            .filter_map(|event| match event {
+                MonitorEvent::AnnouncementDropped { text, reason } => Some((text, reason)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            dropped,
+            vec![
+                ("charm break".to_string(), DropReason::Stale),
+                ("go back in".to_string(), DropReason::Stale),
+            ]
+        );
+        let stats = monitor.lag().stats();
+        assert_eq!((stats.lines, stats.stale_lines), (3, 2));
+        assert!(stats.max_secs >= 120.0 && stats.last_secs < 5.0);
+
+        // Shortened: the timer runs for what's left of its delay
+        let monitor = LogMonitor {
+            max_line_lag: Some(Duration::from_secs(30)),
+            stale_lines: StaleLinePolicy::Shorten,
+            ..create_test_monitor(messages)
+        };
+        let mut subscriber = monitor.events().subscribe();
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let mut batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut LineBuffer::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(immediate_texts(&batch), vec!["charm break"]);
+        let dropped = std::iter::from_fn(|| subscriber.try_recv().ok())
+            .filter(|event| matches!(event, MonitorEvent::AnnouncementDropped { .. }))
+            .count();
+        assert_eq!(dropped, 1);
+        assert!(batch.anchor_timers().is_empty());
+        let delay = batch.timed_delay.values().next().unwrap().delay_seconds;
+        assert!((14..=15).contains(&delay), "delay was {}", delay);
+
+        // A timer that was already due when its line was read isn't started at all
+        let log_data = written_ago(90, "Your flesh begins to liquefy.");
+        let mut reader = BufReader::new(log_data.as_bytes());
+        let mut batch = monitor
+            .process_one_batch(TEST_CHARACTER, &mut reader, &mut LineBuffer::default())
+            .await
+            .unwrap()
+            .unwrap();
+        monitor.drop_stale(batch.anchor_timers());
+        assert!(batch.timed_delay.is_empty());
+        let dropped: Vec<_> = std::iter::from_fn(|| subscriber.try_recv().ok())
+            .filter_map(|event| match event {
+                MonitorEvent::AnnouncementDropped { text, reason } => Some((text, reason)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dropped, vec![("go back in".to_string(), DropReason::Stale)]);
+    }
+
+    // Monitors until an event matching `wanted` is published, then stops
+    async fn monitor_until(monitor: &LogMonitor, wanted: fn(&MonitorEvent) -> bool) {
+        let mut subscriber = monitor.events().subscribe();
+        let shutdown = CancellationToken::new();
+        let stop = async {
+            while !wanted(&subscriber.recv().await.unwrap()) {}
+            shutdown.cancel();
+        };
+        let (result, ()) = tokio::time::timeout(Duration::from_secs(30), async {
+            tokio::join!(monitor.start_monitoring(shutdown.clone()), stop)
+        })
+        .await
+        .expect("monitoring should stop once cancelled");
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_bookmark() {
+        let dir = std::env::temp_dir().join(format!("quarm_resume_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("eqlog_Soandso_pq.proj.txt");
+        std::fs::write(&path, written_ago(0, "Your charm spell has worn off.")).unwrap();
+        let bookmark_file = dir.join("bookmarks.json");
+        let resuming_monitor = || LogMonitor {
+            game_directory: dir.clone(),
+            start_position: StartPosition::Resume,
+            bookmark_file: Some(bookmark_file.clone()),
+            ..create_test_monitor(vec![MessageConfig::Simple {
+                pattern: "charm spell has worn off".to_string(),
+                announcement: "charm break".to_string(),
+                options: TriggerOptions::default(),
+            }])
+        };
+
+        // Without a bookmark, only new lines are read; stopping bookmarks the end
+        monitor_until(&resuming_monitor(), |event| {
+            matches!(event, MonitorEvent::LogOpened { .. })
+        })
+        .await;
+        assert!(bookmark_file.exists());
+
+        // A line written while stopped is picked up on the next start
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(written_ago(0, "Your charm spell has worn off.").as_bytes())
+            .unwrap();
+        let monitor = resuming_monitor();
+        let mut subscriber = monitor.events().subscribe();
+        monitor_until(&monitor, |event| {
+            matches!(event, MonitorEvent::LineMatched(_))
+        })
+        .await;
+        let matched = std::iter::from_fn(|| subscriber.try_recv().ok())
+            .filter(|event| matches!(event, MonitorEvent::LineMatched(_)))
+            .count();
+        assert_eq!(matched, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_timers() {
+        let dir = std::env::temp_dir().join(format!("quarm_shutdown_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("eqlog_Soandso_pq.proj.txt");
+        std::fs::write(&path, "").unwrap();
+
+        let monitor = LogMonitor {
+            game_directory: dir.clone(),
+            ..create_test_monitor(vec![MessageConfig::TimedDelay {
+                pattern: "flesh begins to liquefy".to_string(),
+                announcement: "go back in".to_string(),
+                timer_delay_in_seconds: 600,
+                options: TriggerOptions::default(),
+            }])
+        };
+        let mut subscriber = monitor.events().subscribe();
+        let shutdown = CancellationToken::new();
+
+        let stop = async {
+            let mut wait_for = async |wanted: fn(&MonitorEvent) -> bool| loop {
+                let event = subscriber.recv().await.unwrap();
+                if wanted(&event) {
+                    return event;
+                }
+            };
+            wait_for(|event| matches!(event, MonitorEvent::LogOpened { .. })).await;
+            std::fs::write(&path, written_ago(0, "Your flesh begins to liquefy.")).unwrap();
+            wait_for(|event| matches!(event, MonitorEvent::TimerScheduled { .. })).await;
+            shutdown.cancel();
+            wait_for(|event| matches!(event, MonitorEvent::TimerCancelled { .. })).await
+        };
+        let (result, cancelled) = tokio::time::timeout(Duration::from_secs(30), async {
+            tokio::join!(monitor.start_monitoring(shutdown.clone()), stop)
+        })
+        .await
+        .expect("monitoring should stop once cancelled");
+
+        result.unwrap();
+        assert!(matches!(cancelled, MonitorEvent::TimerCancelled { .. }));
+        assert!(monitor.timers().list().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,184 @@
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDateTime;
+use encoding_rs::Encoding;
+
+// Format of the bracketed prefix EverQuest writes on every line, e.g. `[Mon Oct 16 12:00:00 2026]`,
+// after the leading weekday (which is redundant and not validated)
+const TIMESTAMP_FORMAT: &str = "%b %d %H:%M:%S %Y";
+
+/// A single EverQuest log line split into its timestamp and message body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// When EverQuest wrote the line (game machine's local time)
+    /// None when the timestamp prefix is missing or malformed
+    pub timestamp: Option<NaiveDateTime>,
+    /// Message text after the timestamp, without the line ending
+    /// Holds the whole line when no valid timestamp was found
+    pub body: String,
+}
+
+impl LogLine {
+    /// Decodes and parses a raw log line
+    /// Bytes that aren't valid in the encoding become U+FFFD instead of failing the line
+    pub(crate) fn decode(raw: &[u8], encoding: &'static Encoding) -> Self {
+        Self::parse(&encoding.decode_without_bom_handling(raw).0)
+    }
+
+    /// Parses a raw log line, falling back to the whole line as the body when
+    /// it doesn't start with a valid `[...]` timestamp
+    pub fn parse(raw: &str) -> Self {
+        let line = raw.trim_end_matches(['\r', '\n']);
+
+        match split_timestamp(line) {
+            Some((timestamp, body)) => Self {
+                timestamp: Some(timestamp),
+                body: body.to_string(),
+            },
+            None => Self {
+                timestamp: None,
+                body: line.to_string(),
+            },
+        }
+    }
+}
+
+/// Looks up the encoding logs are written in by its label, e.g. "windows-1252"
+/// Only encodings that keep ASCII as single bytes work, since lines are split on the newline
+/// byte; UTF-16 is rejected
+pub(crate) fn log_encoding(label: &str) -> Result<&'static Encoding> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .context(format!("Unknown log encoding: {}", label))?;
+    if !encoding.is_ascii_compatible() {
+        bail!(
+            "Unsupported log encoding: {} (logs must use an ASCII-compatible encoding)",
+            label
+        );
+    }
+    Ok(encoding)
+}
+
+/// Splits `[<timestamp>] <body>` into a parsed timestamp and the body
+fn split_timestamp(line: &str) -> Option<(NaiveDateTime, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let (timestamp, body) = rest.split_once(']')?;
+    let (_weekday, date) = timestamp.trim().split_once(' ')?;
+    let timestamp = NaiveDateTime::parse_from_str(date.trim(), TIMESTAMP_FORMAT).ok()?;
+    Some((timestamp, body.strip_prefix(' ').unwrap_or(body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    fn datetime(year: i32, month: u32, day: u32, h: u32, m: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(h, m, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parses_real_log_lines() {
+        let samples = [
+            (
+                "[Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n",
+                datetime(2026, 10, 16, 12, 0, 0),
+                "Your charm spell has worn off.",
+            ),
+            (
+                "[Tue Jan 07 21:04:55 2025] Soandso tells you, 'can I get a port?'\r\n",
+                datetime(2025, 1, 7, 21, 4, 55),
+                "Soandso tells you, 'can I get a port?'",
+            ),
+            (
+                "[Sat Mar 01 09:15:32 2025] You have entered The Plane of Fear.",
+                datetime(2025, 3, 1, 9, 15, 32),
+                "You have entered The Plane of Fear.",
+            ),
+            (
+                "[Sun Feb 02 23:59:59 2025] a fire beetle hits YOU for 12 points of damage.",
+                datetime(2025, 2, 2, 23, 59, 59),
+                "a fire beetle hits YOU for 12 points of damage.",
+            ),
+        ];
+
+        for (raw, timestamp, body) in samples {
+            let line = LogLine::parse(raw);
+            assert_eq!(line.timestamp, Some(timestamp), "timestamp of {:?}", raw);
+            assert_eq!(line.body, body);
+        }
+    }
+
+    #[test]
+    fn test_weekday_is_not_validated() {
+        // Oct 16 2026 is a Friday; the weekday is ignored rather than rejected
+        let line = LogLine::parse("[Mon Oct 16 12:00:00 2026] Your charm spell has worn off.");
+        assert_eq!(line.timestamp, Some(datetime(2026, 10, 16, 12, 0, 0)));
+    }
+
+    #[test]
+    fn test_body_keeps_brackets_after_timestamp() {
+        let line = LogLine::parse("[Wed Jan 01 08:05:03 2025] [ANONYMOUS] Soandso <Guild>");
+        assert_eq!(line.timestamp, Some(datetime(2025, 1, 1, 8, 5, 3)));
+        assert_eq!(line.body, "[ANONYMOUS] Soandso <Guild>");
+    }
+
+    #[test]
+    fn test_malformed_lines_keep_whole_text() {
+        let samples = [
+            "Your charm spell has worn off.\n",
+            "[Mon Oct 16 12:00:00 2026 missing bracket",
+            "[Xyz Foo 99 99:99:99 2026] invalid date",
+            "[] empty timestamp",
+            "",
+        ];
+
+        for raw in samples {
+            let line = LogLine::parse(raw);
+            assert_eq!(line.timestamp, None, "timestamp of {:?}", raw);
+            assert_eq!(line.body, raw.trim_end_matches('\n'));
+        }
+    }
+
+    #[test]
+    fn test_multibyte_text_is_preserved() {
+        let line = LogLine::parse("[Mon Oct 16 12:00:00 2026] Brûlé says, 'héllo'\n");
+        assert_eq!(line.body, "Brûlé says, 'héllo'");
+    }
+
+    #[test]
+    fn test_decodes_windows_1252() {
+        let windows_1252 = log_encoding("windows-1252").unwrap();
+        // "Brûlé tells you, 'café'" as EverQuest writes it, which isn't valid UTF-8
+        let raw = b"[Mon Oct 16 12:00:00 2026] Br\xfbl\xe9 tells you, 'caf\xe9'\r\n";
+        let line = LogLine::decode(raw, windows_1252);
+        assert_eq!(line.timestamp, Some(datetime(2026, 10, 16, 12, 0, 0)));
+        assert_eq!(line.body, "Brûlé tells you, 'café'");
+    }
+
+    #[test]
+    fn test_invalid_bytes_are_replaced() {
+        let utf8 = log_encoding("utf-8").unwrap();
+        let line = LogLine::decode(
+            b"[Mon Oct 16 12:00:00 2026] Br\xfbl\xe9 \xff\xfe ok\n",
+            utf8,
+        );
+        assert_eq!(line.timestamp, Some(datetime(2026, 10, 16, 12, 0, 0)));
+        assert_eq!(line.body, "Br\u{FFFD}l\u{FFFD} \u{FFFD}\u{FFFD} ok");
+
+        // Every byte means something in Windows-1252
+        let line = LogLine::decode(b"\x81\x8d\xff", log_encoding("windows-1252").unwrap());
+        assert_eq!(line.body.chars().count(), 3);
+        assert!(!line.body.contains('\u{FFFD}'));
+
+        assert!(log_encoding("klingon").is_err());
+    }
+
+    #[test]
+    fn test_rejects_encodings_not_ascii_compatible() {
+        assert!(log_encoding("utf-16le").is_err());
+        assert!(log_encoding("UTF-16BE").is_err());
+        assert!(log_encoding(" utf-8 ").is_ok());
+    }
+}
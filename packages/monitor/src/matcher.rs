@@ -0,0 +1,146 @@
+use std::cell::OnceCell;
+
+use anyhow::Result;
+
+use quarm_config::{Config, MessageConfig, Profiles};
+
+use crate::game_event::zone_entered;
+use crate::{
+    Announcement, BatchResult, DropReason, EventBus, GameEvent, LineMatch, LogLine, MonitorEvent,
+    PatternSet, TimedMatch, ZoneTracker,
+};
+
+/// Matches log lines against the configured triggers, tracking each character's zone
+/// Holds no audio state, so it can also be used for analysis without a TTS engine
+pub(crate) struct TriggerMatcher {
+    pub(crate) messages: Vec<MessageConfig>,
+    /// Patterns of `messages`, compiled for matching them all at once
+    patterns: PatternSet,
+    /// Start announcements with the name of the character whose log matched
+    pub(crate) prefix_character_name: bool,
+    /// Per-character trigger sets, voices and prefixes
+    pub(crate) profiles: Profiles,
+    /// Zone each character is in, for zone-scoped triggers
+    pub(crate) zone: ZoneTracker,
+    /// Print matches and zone changes as they happen
+    pub(crate) verbose: bool,
+    /// Where matches and zone changes are published
+    pub(crate) events: EventBus,
+}
+
+impl TriggerMatcher {
+    pub(crate) fn new(config: Config) -> Result<Self> {
+        Ok(Self {
+            patterns: PatternSet::new(&config.messages)?,
+            messages: config.messages,
+            prefix_character_name: config.monitor.prefix_character_name,
+            profiles: config.profiles,
+            zone: ZoneTracker::default(),
+            verbose: true,
+            events: EventBus::default(),
+        })
+    }
+
+    /// Adds every message matching a line's body to the batch being collected
+    pub(crate) fn collect_matches(&self, character: &str, line: &LogLine, batch: &mut BatchResult) {
+        // Zone changes apply to the zone-entered line itself and everything after it
+        self.track_zone(character, line);
+
+        let profile = self.profiles.get(character);
+        for (trigger, config) in self.match_message(character, &line.body) {
+            if self.verbose {
+                println!(
+                    "Match found! Log: '{}' -> Announcing: '{}'",
+                    line.body.trim(),
+                    config.announcement()
+                );
+            }
+            let line_match = LineMatch {
+                trigger,
+                character: character.to_string(),
+                timestamp: line.timestamp,
+                body: line.body.clone(),
+                pattern: config.pattern().to_string(),
+                announcement: config.announcement().to_string(),
+            };
+            self.events
+                .publish(MonitorEvent::LineMatched(line_match.clone()));
+            batch.matches.push(line_match);
+
+            let announcement =
+                Announcement::for_character(config, character, profile, self.prefix_character_name);
+            match config {
+                MessageConfig::Simple { .. } => {
+                    if batch.immediate.contains(&announcement) {
+                        self.events.publish(MonitorEvent::AnnouncementDropped {
+                            text: announcement.text,
+                            reason: DropReason::DuplicateInBatch,
+                        });
+                    } else {
+                        batch.immediate.push(announcement);
+                    }
+                }
+                MessageConfig::TimedDelay {
+                    pattern,
+                    timer_delay_in_seconds,
+                    ..
+                } => {
+                    batch.timed_delay.insert(
+                        trigger,
+                        TimedMatch {
+                            pattern: pattern.clone(),
+                            announcement,
+                            delay_seconds: *timer_delay_in_seconds,
+                            written: line.timestamp,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Updates the character's zone if the line is a "You have entered X." message
+    pub(crate) fn track_zone(&self, character: &str, line: &LogLine) {
+        if let Some(zone) = zone_entered(&line.body) {
+            if self.verbose {
+                println!("{} entered zone: {}", character, zone);
+            }
+            self.zone.set(character, Some(zone.to_string()));
+            self.events.publish(MonitorEvent::ZoneChanged {
+                character: character.to_string(),
+                zone: zone.to_string(),
+            });
+        }
+    }
+
+    /// Checks if a log line matches any configured messages
+    /// Returns all matching MessageConfigs with their index in the config
+    /// (supports same pattern with different types)
+    /// Event conditions only classify the line once, and only if a trigger needs it
+    pub(crate) fn match_message(
+        &self,
+        character: &str,
+        line: &str,
+    ) -> Vec<(usize, &MessageConfig)> {
+        let event = OnceCell::new();
+        let zone = self.zone.for_character(character);
+        let profile = self.profiles.get(character);
+        self.patterns
+            .matches(line)
+            .into_iter()
+            .map(|index| (index, &self.messages[index]))
+            .filter(|(_, message_config)| message_config.allowed_for_character(character))
+            .filter(|(_, message_config)| {
+                profile.is_none_or(|profile| profile.enables(message_config))
+            })
+            .filter(|(_, message_config)| message_config.allowed_in_zone(zone.as_deref()))
+            .filter(|(_, message_config)| match message_config.event() {
+                Some(condition) => event
+                    .get_or_init(|| GameEvent::classify(line))
+                    .as_ref()
+                    .is_some_and(|event| event.satisfies(condition)),
+                None => true,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use aho_corasick::AhoCorasick;
+use anyhow::{Context, Result};
+use regex::{Regex, RegexSet};
+
+use quarm_config::MessageConfig;
+
+/// Every trigger's pattern compiled together, so a line is checked against all of them in
+/// one pass: plain-text patterns share an Aho-Corasick automaton, and regex patterns a
+/// `RegexSet`
+pub struct PatternSet {
+    automaton: AhoCorasick,
+    /// Triggers using each distinct plain-text pattern, by automaton pattern ID
+    text_triggers: Vec<Vec<usize>>,
+    regexes: RegexSet,
+    /// Trigger using each regex, by index in the set
+    regex_triggers: Vec<usize>,
+    /// Triggers with an empty pattern, which match every line
+    always: Vec<usize>,
+}
+
+impl PatternSet {
+    /// Compiles the patterns of the given messages, failing on an invalid regex
+    pub fn new(messages: &[MessageConfig]) -> Result<Self> {
+        let mut texts: Vec<&str> = Vec::new();
+        // Automaton pattern ID of each distinct plain-text pattern
+        let mut text_ids: HashMap<&str, usize> = HashMap::new();
+        let mut text_triggers: Vec<Vec<usize>> = Vec::new();
+        let mut regexes = Vec::new();
+        let mut regex_triggers = Vec::new();
+        let mut always = Vec::new();
+
+        for (index, message) in messages.iter().enumerate() {
+            let pattern = message.pattern();
+            if pattern.is_empty() {
+                always.push(index);
+            } else if message.is_regex() {
+                // Checked one at a time so the error names the offending trigger
+                Regex::new(pattern).context(format!(
+                    "Invalid regex in trigger {} ('{}')",
+                    index + 1,
+                    message.announcement()
+                ))?;
+                regexes.push(pattern);
+                regex_triggers.push(index);
+            } else if let Some(&id) = text_ids.get(pattern) {
+                text_triggers[id].push(index);
+            } else {
+                text_ids.insert(pattern, texts.len());
+                texts.push(pattern);
+                text_triggers.push(vec![index]);
+            }
+        }
+
+        Ok(Self {
+            automaton: AhoCorasick::new(&texts).context("Failed to build pattern automaton")?,
+            text_triggers,
+            regexes: RegexSet::new(&regexes).context("Failed to build regex set")?,
+            regex_triggers,
+            always,
+        })
+    }
+
+    /// Returns the indices of the messages whose pattern matches the line, in config order
+    pub fn matches(&self, line: &str) -> Vec<usize> {
+        let mut found: Vec<usize> = self
+            .automaton
+            .find_overlapping_iter(line)
+            .map(|m| m.pattern().as_usize())
+            .collect();
+        // A pattern can occur more than once in a line
+        found.sort_unstable();
+        found.dedup();
+
+        let mut matched = self.always.clone();
+        for id in found {
+            matched.extend_from_slice(&self.text_triggers[id]);
+        }
+        if !self.regex_triggers.is_empty() {
+            matched.extend(
+                self.regexes
+                    .matches(line)
+                    .iter()
+                    .map(|i| self.regex_triggers[i]),
+            );
+        }
+        matched.sort_unstable();
+        matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quarm_config::TriggerOptions;
+
+    fn message(pattern: &str, regex: bool) -> MessageConfig {
+        MessageConfig::Simple {
+            pattern: pattern.to_string(),
+            announcement: pattern.to_string(),
+            options: TriggerOptions {
+                regex,
+                ..TriggerOptions::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_matches_same_triggers_as_linear_scan() {
+        let messages = vec![
+            message("spell has worn off", false),
+            message("", false),
+            message("Mesmerization spell", false),
+            message(r"^(\w+) tells you, '", true),
+            message("spell has worn off", false),
+            message("worn", false),
+            message(r"hits YOU for \d{3,} points", true),
+        ];
+        let patterns = PatternSet::new(&messages).unwrap();
+
+        let lines = [
+            "Your Mesmerization spell has worn off.",
+            "Soandso tells you, 'your spell has worn off'",
+            "A fire giant hits YOU for 512 points of damage.",
+            "A fire giant hits YOU for 12 points of damage.",
+            "You have entered The Plane of Fear.",
+        ];
+        for line in lines {
+            let expected: Vec<usize> = messages
+                .iter()
+                .enumerate()
+                .filter(|(_, message)| {
+                    if message.is_regex() {
+                        Regex::new(message.pattern()).unwrap().is_match(line)
+                    } else {
+                        line.contains(message.pattern())
+                    }
+                })
+                .map(|(index, _)| index)
+                .collect();
+            assert_eq!(patterns.matches(line), expected, "line: {}", line);
+        }
+
+        assert_eq!(patterns.matches(lines[0]), vec![0, 1, 2, 4, 5]);
+        assert_eq!(patterns.matches(lines[1]), vec![0, 1, 3, 4, 5]);
+        assert_eq!(patterns.matches(lines[2]), vec![1, 6]);
+    }
+
+    #[test]
+    fn test_invalid_regex_names_trigger() {
+        let messages = vec![message("fine", false), message("(unclosed", true)];
+        let error = PatternSet::new(&messages).err().unwrap();
+        assert!(format!("{:#}", error).contains("trigger 2"));
+
+        // The same text is fine as a plain pattern
+        assert!(PatternSet::new(&[message("(unclosed", false)]).is_ok());
+    }
+}
@@ -0,0 +1,205 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+// Formats accepted for replay start and end times
+const TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// How fast a replay plays back a recorded log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Lines are paced by their timestamps and timers run, sped up by this factor
+    Multiplier(f64),
+    /// Lines are processed as fast as possible and timers fire right away
+    Instant,
+}
+
+impl ReplaySpeed {
+    /// Plays back at the speed the log was recorded
+    pub const REALTIME: Self = Self::Multiplier(1.0);
+
+    /// Scales a recorded duration to playback time
+    pub fn scale(&self, duration: Duration) -> Duration {
+        match self {
+            Self::Multiplier(factor) => duration.div_f64(*factor),
+            Self::Instant => Duration::ZERO,
+        }
+    }
+}
+
+impl Default for ReplaySpeed {
+    fn default() -> Self {
+        Self::REALTIME
+    }
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    /// Parses `instant`, or a multiplier like `10x` or `2.5`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("instant") {
+            return Ok(Self::Instant);
+        }
+
+        let factor: f64 = s
+            .strip_suffix(['x', 'X'])
+            .unwrap_or(s)
+            .parse()
+            .map_err(|_| {
+                format!(
+                    "Invalid replay speed '{}' (expected e.g. 1x, 10x or instant)",
+                    s
+                )
+            })?;
+        if !(factor.is_finite() && factor > 0.0) {
+            return Err(format!("Replay speed must be positive, got '{}'", s));
+        }
+        Ok(Self::Multiplier(factor))
+    }
+}
+
+/// Options for replaying a recorded log through the trigger engine
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+    pub speed: ReplaySpeed,
+    /// Lines stamped before this time are skipped
+    pub start: Option<NaiveDateTime>,
+    /// The replay stops at the first line stamped after this time
+    pub end: Option<NaiveDateTime>,
+}
+
+/// Counts from a finished replay
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReplaySummary {
+    /// Lines sent through the trigger engine (within the time range)
+    pub lines: usize,
+    /// Trigger matches, including ones deduplicated before speaking
+    pub matches: usize,
+    /// Whether the replay was stopped before it finished
+    pub stopped: bool,
+}
+
+/// Parses a replay start or end time, e.g. `2026-10-16 21:30:00`
+pub fn parse_replay_time(s: &str) -> Result<NaiveDateTime, String> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s.trim(), format).ok())
+        .ok_or_else(|| format!("Invalid time '{}' (expected YYYY-MM-DD HH:MM:SS)", s))
+}
+
+/// Where a line falls relative to the replay's time range
+#[derive(Debug, PartialEq)]
+pub(crate) enum RangePosition {
+    Before,
+    Within,
+    After,
+}
+
+impl ReplayOptions {
+    /// Returns where a line stamped at `timestamp` falls; without a timestamp it only
+    /// falls within the range when there is no start time
+    pub(crate) fn position(&self, timestamp: Option<NaiveDateTime>) -> RangePosition {
+        match timestamp {
+            Some(t) if self.start.is_some_and(|start| t < start) => RangePosition::Before,
+            Some(t) if self.end.is_some_and(|end| t > end) => RangePosition::After,
+            None if self.start.is_some() => RangePosition::Before,
+            _ => RangePosition::Within,
+        }
+    }
+}
+
+/// Delays replayed lines by the gaps between their original timestamps
+pub(crate) struct Pacer {
+    speed: ReplaySpeed,
+    last: Option<NaiveDateTime>,
+}
+
+impl Pacer {
+    pub(crate) fn new(speed: ReplaySpeed) -> Self {
+        Self { speed, last: None }
+    }
+
+    /// Returns how long to wait before replaying a line with this timestamp
+    pub(crate) fn delay_before(&mut self, timestamp: NaiveDateTime) -> Duration {
+        let gap = self
+            .last
+            .and_then(|last| (timestamp - last).to_std().ok())
+            .unwrap_or_default();
+        self.last = Some(timestamp);
+        self.speed.scale(gap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveDateTime {
+        parse_replay_time(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_replay_speed() {
+        assert_eq!("1x".parse(), Ok(ReplaySpeed::Multiplier(1.0)));
+        assert_eq!("10X".parse(), Ok(ReplaySpeed::Multiplier(10.0)));
+        assert_eq!("2.5".parse(), Ok(ReplaySpeed::Multiplier(2.5)));
+        assert_eq!("Instant".parse(), Ok(ReplaySpeed::Instant));
+        assert!("0x".parse::<ReplaySpeed>().is_err());
+        assert!("fast".parse::<ReplaySpeed>().is_err());
+    }
+
+    #[test]
+    fn test_pacer_scales_gaps() {
+        let mut pacer = Pacer::new(ReplaySpeed::Multiplier(10.0));
+        assert_eq!(
+            pacer.delay_before(time("2026-10-16 12:00:00")),
+            Duration::ZERO
+        );
+        assert_eq!(
+            pacer.delay_before(time("2026-10-16 12:00:30")),
+            Duration::from_secs(3)
+        );
+        // Clock going backwards doesn't wait
+        assert_eq!(
+            pacer.delay_before(time("2026-10-16 11:00:00")),
+            Duration::ZERO
+        );
+
+        let mut pacer = Pacer::new(ReplaySpeed::Instant);
+        pacer.delay_before(time("2026-10-16 12:00:00"));
+        assert_eq!(
+            pacer.delay_before(time("2026-10-16 13:00:00")),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_range_position() {
+        let options = ReplayOptions {
+            start: Some(time("2026-10-16 12:00:00")),
+            end: Some(time("2026-10-16 13:00")),
+            ..ReplayOptions::default()
+        };
+        assert_eq!(
+            options.position(Some(time("2026-10-16 11:59:59"))),
+            RangePosition::Before
+        );
+        assert_eq!(
+            options.position(Some(time("2026-10-16T12:30:00"))),
+            RangePosition::Within
+        );
+        assert_eq!(
+            options.position(Some(time("2026-10-16 13:00:01"))),
+            RangePosition::After
+        );
+        assert_eq!(
+            ReplayOptions::default().position(None),
+            RangePosition::Within
+        );
+        assert!(parse_replay_time("yesterday").is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use quarm_config::{Config, MessageConfig};
+
+use crate::log_line::log_encoding;
+use crate::matcher::TriggerMatcher;
+use crate::tailer::character_from_log_path;
+use crate::{BatchResult, LogLine};
+
+/// Everything the configured triggers would have done over a recorded log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DryRunReport {
+    pub log_file: String,
+    pub character: String,
+    /// Lines read from the log
+    pub lines: usize,
+    /// Every match, in log order
+    pub matches: Vec<ReportMatch>,
+    /// Match counts for every trigger, in config order
+    pub triggers: Vec<TriggerCount>,
+    /// Triggers that never matched, in config order
+    pub never_matched: Vec<TriggerCount>,
+}
+
+/// One trigger matching one log line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportMatch {
+    pub timestamp: Option<NaiveDateTime>,
+    pub line: String,
+    /// Index of the trigger in the config's messages
+    pub trigger: usize,
+    pub pattern: String,
+    pub announcement: String,
+    /// Timer scheduled by a timed delay trigger
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timer: Option<ReportTimer>,
+}
+
+/// A timer scheduled by a match
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportTimer {
+    pub delay_seconds: u64,
+    /// When the timer would fire, if the line had a timestamp
+    pub fires_at: Option<NaiveDateTime>,
+    /// Whether a later match of the same trigger replaced it before it fired
+    pub cancelled: bool,
+}
+
+/// How often a trigger matched
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TriggerCount {
+    pub trigger: usize,
+    pub pattern: String,
+    pub announcement: String,
+    pub matches: usize,
+    pub timers_cancelled: usize,
+}
+
+/// Runs a recorded log through the configured triggers without speaking anything,
+/// reporting every match and timer
+pub async fn dry_run(config: &Config, log_path: &Path) -> Result<DryRunReport> {
+    let character = character_from_log_path(log_path);
+    let encoding = log_encoding(&config.monitor.log_encoding)?;
+    let mut matcher = TriggerMatcher::new(config.clone())?;
+    matcher.verbose = false;
+
+    let file = tokio::fs::File::open(log_path)
+        .await
+        .context(format!("Failed to open: {}", log_path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut line_buffer = Vec::new();
+
+    let mut lines = 0;
+    let mut matches: Vec<ReportMatch> = Vec::new();
+    // Index into `matches` of the pending timer for each timed delay trigger
+    let mut pending_timers: HashMap<usize, usize> = HashMap::new();
+
+    loop {
+        line_buffer.clear();
+        let bytes_read = reader
+            .read_until(b'\n', &mut line_buffer)
+            .await
+            .context("Failed to read line from log file")?;
+        if bytes_read == 0 {
+            break;
+        }
+        lines += 1;
+
+        let line = LogLine::decode(&line_buffer, encoding);
+        let mut batch = BatchResult::default();
+        matcher.collect_matches(&character, &line, &mut batch);
+
+        for line_match in batch.matches {
+            let message = &config.messages[line_match.trigger];
+            let timer = match message {
+                MessageConfig::TimedDelay {
+                    timer_delay_in_seconds,
+                    ..
+                } => {
+                    // A new match replaces its trigger's timer if it hasn't fired yet (debounce)
+                    if let Some(previous) = pending_timers.insert(line_match.trigger, matches.len())
+                        && let Some(timer) = matches[previous].timer.as_mut()
+                        && timer
+                            .fires_at
+                            .is_none_or(|fires_at| line.timestamp.is_none_or(|t| t < fires_at))
+                    {
+                        timer.cancelled = true;
+                    }
+                    Some(ReportTimer {
+                        delay_seconds: *timer_delay_in_seconds,
+                        fires_at: line
+                            .timestamp
+                            .map(|t| t + TimeDelta::seconds(*timer_delay_in_seconds as i64)),
+                        cancelled: false,
+                    })
+                }
+                MessageConfig::Simple { .. } => None,
+            };
+
+            matches.push(ReportMatch {
+                timestamp: line_match.timestamp,
+                line: line_match.body,
+                trigger: line_match.trigger,
+                pattern: line_match.pattern,
+                announcement: line_match.announcement,
+                timer,
+            });
+        }
+    }
+
+    let triggers: Vec<TriggerCount> = config
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let trigger_matches = matches.iter().filter(|m| m.trigger == index);
+            TriggerCount {
+                trigger: index,
+                pattern: message.pattern().to_string(),
+                announcement: message.announcement().to_string(),
+                matches: trigger_matches.clone().count(),
+                timers_cancelled: trigger_matches
+                    .filter(|m| m.timer.as_ref().is_some_and(|timer| timer.cancelled))
+                    .count(),
+            }
+        })
+        .collect();
+    let never_matched = triggers
+        .iter()
+        .filter(|count| count.matches == 0)
+        .cloned()
+        .collect();
+
+    Ok(DryRunReport {
+        log_file: log_path.display().to_string(),
+        character,
+        lines,
+        matches,
+        triggers,
+        never_matched,
+    })
+}
+
+impl fmt::Display for DryRunReport {
+    /// Human-readable report: every match, then per-trigger counts
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Dry run of {} ({})", self.log_file, self.character)?;
+        writeln!(f, "{} lines, {} matches", self.lines, self.matches.len())?;
+
+        writeln!(f, "\nMatches:")?;
+        for m in &self.matches {
+            let timestamp = m
+                .timestamp
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "(no timestamp)".to_string());
+            write!(f, "  {}  '{}' -> '{}'", timestamp, m.line, m.announcement)?;
+            if let Some(timer) = &m.timer {
+                write!(f, " [timer {}s", timer.delay_seconds)?;
+                if timer.cancelled {
+                    write!(f, ", cancelled")?;
+                }
+                write!(f, "]")?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "\nTriggers:")?;
+        for count in &self.triggers {
+            write!(
+                f,
+                "  {:>5}  '{}' -> '{}'",
+                count.matches, count.pattern, count.announcement
+            )?;
+            if count.timers_cancelled > 0 {
+                write!(f, " ({} timers cancelled)", count.timers_cancelled)?;
+            }
+            writeln!(f)?;
+        }
+
+        if !self.never_matched.is_empty() {
+            writeln!(f, "\nNever matched:")?;
+            for count in &self.never_matched {
+                writeln!(f, "  '{}' -> '{}'", count.pattern, count.announcement)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quarm_config::{EventCondition, EventKind, TriggerOptions};
+
+    #[tokio::test]
+    async fn test_dry_run_report() {
+        let config = Config {
+            messages: vec![
+                MessageConfig::Simple {
+                    pattern: "charm spell has worn off".to_string(),
+                    announcement: "charm break".to_string(),
+                    options: TriggerOptions::default(),
+                },
+                MessageConfig::TimedDelay {
+                    pattern: "flesh begins to liquefy".to_string(),
+                    announcement: "go back in".to_string(),
+                    timer_delay_in_seconds: 22,
+                    options: TriggerOptions::default(),
+                },
+                MessageConfig::Simple {
+                    pattern: "Root spell has worn off".to_string(),
+                    announcement: "root break".to_string(),
+                    options: TriggerOptions::default(),
+                },
+            ],
+            ..Config::default()
+        };
+
+        let path =
+            std::env::temp_dir().join(format!("eqlog_Soandso_dryrun{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "[Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n\
+             [Fri Oct 16 12:00:05 2026] Your flesh begins to liquefy.\n\
+             [Fri Oct 16 12:00:10 2026] Your flesh begins to liquefy.\n\
+             [Fri Oct 16 12:01:00 2026] Your flesh begins to liquefy.\n\
+             [Fri Oct 16 12:02:00 2026] Your charm spell has worn off.\n",
+        )
+        .unwrap();
+
+        let report = dry_run(&config, &path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.character, "Soandso");
+        assert_eq!(report.lines, 5);
+        assert_eq!(report.matches.len(), 5);
+
+        // The second liquefy replaced the first before it fired; the third came after
+        let timers: Vec<bool> = report
+            .matches
+            .iter()
+            .filter_map(|m| m.timer.as_ref().map(|timer| timer.cancelled))
+            .collect();
+        assert_eq!(timers, vec![true, false, false]);
+        assert_eq!(
+            report.matches[1].timer.as_ref().unwrap().fires_at,
+            Some(crate::parse_replay_time("2026-10-16 12:00:27").unwrap())
+        );
+
+        let counts: Vec<(usize, usize)> = report
+            .triggers
+            .iter()
+            .map(|count| (count.matches, count.timers_cancelled))
+            .collect();
+        assert_eq!(counts, vec![(2, 0), (3, 1), (0, 0)]);
+        assert_eq!(report.never_matched.len(), 1);
+        assert_eq!(report.never_matched[0].announcement, "root break");
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["never_matched"][0]["trigger"], 2);
+        assert!(json["matches"][0].get("timer").is_none());
+
+        let text = report.to_string();
+        assert!(text.contains("5 lines, 5 matches"));
+        assert!(text.contains("[timer 22s, cancelled]"));
+        assert!(text.contains("Never matched:\n  'Root spell has worn off' -> 'root break'"));
+    }
+
+    #[tokio::test]
+    async fn test_event_timers_kept_apart() {
+        // Event-only triggers all have an empty pattern, but don't replace each other's timers
+        let timed_event = |announcement: &str, kind| MessageConfig::TimedDelay {
+            pattern: String::new(),
+            announcement: announcement.to_string(),
+            timer_delay_in_seconds: 60,
+            options: TriggerOptions {
+                event: Some(EventCondition::kind(kind)),
+                ..TriggerOptions::default()
+            },
+        };
+        let config = Config {
+            messages: vec![
+                timed_event("reply", EventKind::Tell),
+                timed_event("heal up", EventKind::Damage),
+            ],
+            ..Config::default()
+        };
+
+        let path = std::env::temp_dir().join(format!(
+            "eqlog_Soandso_dryrun_events{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "[Fri Oct 16 12:00:00 2026] Soandso tells you, 'hi'\n\
+             [Fri Oct 16 12:00:01 2026] Lord Nagafen hits YOU for 250 points of damage.\n",
+        )
+        .unwrap();
+
+        let report = dry_run(&config, &path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let cancelled: Vec<bool> = report
+            .matches
+            .iter()
+            .filter_map(|m| m.timer.as_ref().map(|timer| timer.cancelled))
+            .collect();
+        assert_eq!(cancelled, vec![false, false]);
+    }
+}
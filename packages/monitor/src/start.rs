@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Local, TimeDelta};
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+
+use quarm_config::StartPosition;
+
+use crate::history;
+use crate::log_line::LogLine;
+
+/// Position reached in each log when monitoring last stopped, as saved in the bookmark file
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Bookmarks {
+    /// Byte offset of the next unread line, by log path
+    logs: BTreeMap<PathBuf, u64>,
+}
+
+impl Bookmarks {
+    /// Reads the bookmark file, with no bookmarks if it doesn't exist yet
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .context(format!("Failed to parse bookmarks: {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).context(format!("Failed to read bookmarks: {}", path.display())),
+        }
+    }
+
+    /// Writes the bookmark file
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize bookmarks")?;
+        crate::replace_file(path, json.as_bytes())
+    }
+
+    pub(crate) fn set(&mut self, log: PathBuf, offset: u64) {
+        self.logs.insert(log, offset);
+    }
+}
+
+/// Offset to start reading a log at, given where it ends
+/// A bookmark past the end of the log means it was cleared since, so it's read from the start
+pub(crate) fn start_offset(
+    path: &Path,
+    len: u64,
+    position: StartPosition,
+    bookmarks: &Bookmarks,
+    encoding: &'static Encoding,
+) -> Result<u64> {
+    Ok(match position {
+        StartPosition::End => len,
+        StartPosition::Resume => match bookmarks.logs.get(path) {
+            Some(&offset) if offset <= len => offset,
+            Some(_) => 0,
+            None => len,
+        },
+        StartPosition::LastLines { lines } => {
+            let mut start = len;
+            let mut counted = 0;
+            history::scan_back(path, len, |raw, offset| {
+                // Nothing follows the final newline
+                if offset == len && raw.is_empty() {
+                    return false;
+                }
+                counted += 1;
+                if counted > lines {
+                    return true;
+                }
+                start = offset;
+                false
+            })?;
+            start
+        }
+        StartPosition::LastSeconds { seconds } => {
+            let since = Local::now().naive_local() - TimeDelta::seconds(seconds as i64);
+            let mut start = len;
+            history::scan_back(path, len, |raw, offset| {
+                let line = LogLine::decode(raw, encoding);
+                if line.timestamp.is_some_and(|timestamp| timestamp < since) {
+                    return true;
+                }
+                if offset < len {
+                    start = offset;
+                }
+                false
+            })?;
+            start
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written_ago(seconds: i64, body: &str) -> String {
+        let written = Local::now() - TimeDelta::seconds(seconds);
+        format!("{} {}\n", written.format("[%a %b %d %H:%M:%S %Y]"), body)
+    }
+
+    #[test]
+    fn test_start_offsets() {
+        let path = std::env::temp_dir().join(format!("quarm_start_{}.txt", std::process::id()));
+        let lines = [
+            written_ago(600, "You have entered The Plane of Fear."),
+            written_ago(90, "Your charm spell has worn off."),
+            written_ago(30, "Your flesh begins to liquefy."),
+            written_ago(10, "You feel less charming."),
+        ];
+        let log = lines.concat();
+        std::fs::write(&path, &log).unwrap();
+        let len = log.len() as u64;
+        let offset_of = |index: usize| lines[..index].concat().len() as u64;
+
+        let mut bookmarks = Bookmarks::default();
+        let start = |position, bookmarks: &Bookmarks| {
+            start_offset(&path, len, position, bookmarks, encoding_rs::WINDOWS_1252).unwrap()
+        };
+        assert_eq!(start(StartPosition::End, &bookmarks), len);
+        assert_eq!(
+            start(StartPosition::LastLines { lines: 2 }, &bookmarks),
+            offset_of(2)
+        );
+        assert_eq!(
+            start(StartPosition::LastLines { lines: 0 }, &bookmarks),
+            len
+        );
+        assert_eq!(
+            start(StartPosition::LastLines { lines: 100 }, &bookmarks),
+            0
+        );
+        assert_eq!(
+            start(StartPosition::LastSeconds { seconds: 120 }, &bookmarks),
+            offset_of(1)
+        );
+        assert_eq!(
+            start(StartPosition::LastSeconds { seconds: 1 }, &bookmarks),
+            len
+        );
+
+        // No bookmark yet: only new lines
+        assert_eq!(start(StartPosition::Resume, &bookmarks), len);
+        bookmarks.set(path.clone(), offset_of(3));
+        assert_eq!(start(StartPosition::Resume, &bookmarks), offset_of(3));
+        // The log was cleared since
+        bookmarks.set(path.clone(), len + 1);
+        assert_eq!(start(StartPosition::Resume, &bookmarks), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bookmarks_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("quarm_bookmarks_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Bookmarks::load(&path).unwrap(), Bookmarks::default());
+
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.set(PathBuf::from("/eq/Logs/eqlog_Soandso_pq.proj.txt"), 1234);
+        bookmarks.save(&path).unwrap();
+        assert_eq!(Bookmarks::load(&path).unwrap(), bookmarks);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
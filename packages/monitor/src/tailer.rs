@@ -0,0 +1,406 @@
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncSeek, AsyncSeekExt, BufReader};
+
+// How long the end of a line the game hasn't finished writing is waited for, before the
+// part written so far is taken as the whole line
+pub(crate) const PARTIAL_LINE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A log file being followed, tagged with the character who writes it
+pub(crate) struct LogTailer {
+    pub(crate) path: PathBuf,
+    pub(crate) character: String,
+    pub(crate) reader: BufReader<File>,
+    pub(crate) line_buffer: LineBuffer,
+    /// Identity of the open file, to notice when the path points at a new file
+    identity: FileIdentity,
+    /// When the log was found deleted, if it hasn't come back since
+    pub(crate) missing_since: Option<Instant>,
+}
+
+/// What identifies a file across renames and truncation
+/// Device and inode on Unix; volume serial number and file index on Windows, where the
+/// creation time can't be used since it's kept when a file is deleted and recreated under
+/// the same name; creation time elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    #[cfg(unix)]
+    dev_ino: (u64, u64),
+    #[cfg(windows)]
+    volume_index: (u32, u64),
+    #[cfg(not(any(unix, windows)))]
+    created: Option<std::time::SystemTime>,
+}
+
+impl FileIdentity {
+    async fn of(file: &File) -> std::io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let metadata = file.metadata().await?;
+            Ok(Self {
+                dev_ino: (metadata.dev(), metadata.ino()),
+            })
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::io::AsRawHandle;
+            use windows_sys::Win32::Storage::FileSystem::{
+                BY_HANDLE_FILE_INFORMATION, GetFileInformationByHandle,
+            };
+
+            let mut info = BY_HANDLE_FILE_INFORMATION::default();
+            // SAFETY: the handle stays open for as long as `file` is borrowed, and `info` is
+            // a valid place for the call to write to
+            if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let index = (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow);
+            Ok(Self {
+                volume_index: (info.dwVolumeSerialNumber, index),
+            })
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            let metadata = file.metadata().await?;
+            Ok(Self {
+                created: metadata.created().ok(),
+            })
+        }
+    }
+}
+
+impl LogTailer {
+    /// Opens a log positioned to read from the given byte offset
+    pub(crate) async fn open(path: PathBuf, offset: u64) -> Result<Self> {
+        let (reader, identity) = open_at(&path, SeekFrom::Start(offset)).await?;
+
+        Ok(Self {
+            character: character_from_log_path(&path),
+            path,
+            reader,
+            line_buffer: LineBuffer::default(),
+            identity,
+            missing_since: None,
+        })
+    }
+
+    /// Byte offset of the next line to be read, including the start of an unfinished one
+    pub(crate) async fn position(&mut self) -> Result<u64> {
+        // A read cancelled part way (e.g. by stopping) leaves the file busy until it finishes
+        let mut file = Pin::new(self.reader.get_mut());
+        std::future::poll_fn(|cx| file.as_mut().poll_complete(cx))
+            .await
+            .context(format!("Failed to read from: {}", self.path.display()))?;
+
+        let position = self.reader.stream_position().await.context(format!(
+            "Failed to read position in: {}",
+            self.path.display()
+        ))?;
+        Ok(position - self.line_buffer.pending_len() as u64)
+    }
+
+    /// Checks whether the log was truncated, replaced or deleted since it was opened,
+    /// repositioning so new lines are read again
+    /// - Truncated (shorter than what was read): reads again from the start
+    /// - Replaced by a different file at the same path: reopens it from the start
+    /// - Deleted: marks it missing until a file appears at the path again
+    ///
+    /// Returns whether the log is being read again from the start
+    pub(crate) async fn check_file(&mut self) -> Result<bool> {
+        let file = match File::open(&self.path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                if self.missing_since.is_none() {
+                    println!("Log deleted: {:?}", self.path);
+                    self.missing_since = Some(Instant::now());
+                }
+                return Ok(false);
+            }
+            Err(e) => {
+                return Err(e).context(format!("Failed to open: {}", self.path.display()));
+            }
+        };
+        let metadata = file
+            .metadata()
+            .await
+            .context(format!("Failed to read metadata: {}", self.path.display()))?;
+        let identity = FileIdentity::of(&file)
+            .await
+            .context(format!("Failed to identify: {}", self.path.display()))?;
+
+        if self.missing_since.is_some() || identity != self.identity {
+            println!("Log replaced, reading from the start: {:?}", self.path);
+            (self.reader, self.identity) = open_at(&self.path, SeekFrom::Start(0)).await?;
+            self.line_buffer = LineBuffer::default();
+            self.missing_since = None;
+            return Ok(true);
+        }
+
+        let position = self
+            .reader
+            .stream_position()
+            .await
+            .context("Failed to read position in log file")?;
+        if metadata.len() < position {
+            println!("Log truncated, reading from the start: {:?}", self.path);
+            self.reader
+                .seek(SeekFrom::Start(0))
+                .await
+                .context("Failed to seek to start of log file")?;
+            self.line_buffer = LineBuffer::default();
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+}
+
+/// Collects log lines as they're written, holding on to a line the game has only written
+/// part of until the rest arrives
+#[derive(Debug, Default)]
+pub(crate) struct LineBuffer {
+    bytes: Vec<u8>,
+    /// Whether `bytes` holds a line that was already returned
+    complete: bool,
+    /// When the unfinished line in `bytes` was first seen
+    partial_since: Option<Instant>,
+}
+
+impl LineBuffer {
+    /// Reads the next whole line, including its newline, or None if there isn't one yet
+    /// An unfinished line counts as whole once it has waited for `PARTIAL_LINE_TIMEOUT`
+    pub(crate) async fn read_line<R>(&mut self, reader: &mut R) -> std::io::Result<Option<&[u8]>>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        if self.complete {
+            self.bytes.clear();
+            self.complete = false;
+        }
+
+        reader.read_until(b'\n', &mut self.bytes).await?;
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+
+        if !self.bytes.ends_with(b"\n") {
+            let since = *self.partial_since.get_or_insert_with(Instant::now);
+            if since.elapsed() < PARTIAL_LINE_TIMEOUT {
+                return Ok(None);
+            }
+        }
+        self.partial_since = None;
+        self.complete = true;
+        Ok(Some(&self.bytes))
+    }
+
+    /// Bytes read of a line that hasn't been returned yet
+    pub(crate) fn pending_len(&self) -> usize {
+        if self.complete { 0 } else { self.bytes.len() }
+    }
+}
+
+/// Opens a file for tailing at the given position, returning its identity
+async fn open_at(path: &Path, position: SeekFrom) -> Result<(BufReader<File>, FileIdentity)> {
+    let file = File::open(path)
+        .await
+        .context(format!("Failed to open: {}", path.display()))?;
+    let identity = FileIdentity::of(&file)
+        .await
+        .context(format!("Failed to identify: {}", path.display()))?;
+
+    let mut reader = BufReader::new(file);
+    reader
+        .seek(position)
+        .await
+        .context("Failed to seek in log file")?;
+
+    Ok((reader, identity))
+}
+
+/// Returns the character name from a log file name like `eqlog_Soandso_pq.proj.txt`
+/// Falls back to the whole file name when it doesn't follow that form
+pub(crate) fn character_from_log_path(path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    file_name
+        .strip_prefix(crate::LOG_FILE_PREFIX)
+        .and_then(|rest| rest.split(['_', '.']).next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_character_from_log_path() {
+        assert_eq!(
+            character_from_log_path(Path::new("/eq/Logs/eqlog_Soandso_pq.proj.txt")),
+            "Soandso"
+        );
+        assert_eq!(
+            character_from_log_path(Path::new("eqlog_Otherguy.txt")),
+            "Otherguy"
+        );
+        assert_eq!(
+            character_from_log_path(Path::new("eqlog_.txt")),
+            "eqlog_.txt"
+        );
+    }
+
+    // Reads the next complete line from the tailer, if one is available
+    async fn next_line(tailer: &mut LogTailer) -> Option<String> {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut line = String::new();
+        match tailer.reader.read_line(&mut line).await.unwrap() {
+            0 => None,
+            _ => Some(line),
+        }
+    }
+
+    fn temp_log(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("eqlog_{}_{}.txt", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_truncated_log_is_read_from_start() {
+        let path = temp_log("Truncated");
+        std::fs::write(&path, "[Fri Oct 16 12:00:00 2026] old line one\n").unwrap();
+
+        let len = std::fs::metadata(&path).unwrap().len();
+        let mut tailer = LogTailer::open(path.clone(), len).await.unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"[Fri Oct 16 12:00:01 2026] old line two\n")
+            .unwrap();
+        assert_eq!(
+            next_line(&mut tailer).await.as_deref(),
+            Some("[Fri Oct 16 12:00:01 2026] old line two\n")
+        );
+
+        // Clear the log mid-tail and write something shorter than what was read
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(next_line(&mut tailer).await, None);
+
+        tailer.check_file().await.unwrap();
+        assert_eq!(next_line(&mut tailer).await.as_deref(), Some("new\n"));
+
+        // Nothing changes when the file only grew
+        tailer.check_file().await.unwrap();
+        assert_eq!(next_line(&mut tailer).await, None);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deleted_and_replaced_log_is_reopened() {
+        let path = temp_log("Replaced");
+        std::fs::write(&path, "[Fri Oct 16 12:00:00 2026] old line\n").unwrap();
+        let len = std::fs::metadata(&path).unwrap().len();
+        let mut tailer = LogTailer::open(path.clone(), len).await.unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        tailer.check_file().await.unwrap();
+        assert!(tailer.missing_since.is_some());
+
+        // A new file at the same path is read from its first line
+        std::fs::write(&path, "[Fri Oct 16 12:05:00 2026] first new line\n").unwrap();
+        tailer.check_file().await.unwrap();
+        assert!(tailer.missing_since.is_none());
+        assert_eq!(
+            next_line(&mut tailer).await.as_deref(),
+            Some("[Fri Oct 16 12:05:00 2026] first new line\n")
+        );
+
+        // Replaced by a rename without being seen missing (e.g. moved aside by a log rotator)
+        let moved = temp_log("Replaced_moved");
+        std::fs::rename(&path, &moved).unwrap();
+        std::fs::write(&path, "[Fri Oct 16 12:10:00 2026] after rotation\n").unwrap();
+        tailer.check_file().await.unwrap();
+        assert_eq!(
+            next_line(&mut tailer).await.as_deref(),
+            Some("[Fri Oct 16 12:10:00 2026] after rotation\n")
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&moved).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_line_written_in_two_chunks() {
+        let path = temp_log("Chunks");
+        std::fs::write(&path, "").unwrap();
+        let mut tailer = LogTailer::open(path.clone(), 0).await.unwrap();
+        let append = |text: &str| {
+            std::fs::OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .write_all(text.as_bytes())
+                .unwrap()
+        };
+
+        // The first half waits for the rest
+        append("[Fri Oct 16 12:00:00 2026] Your charm spell");
+        let line = tailer
+            .line_buffer
+            .read_line(&mut tailer.reader)
+            .await
+            .unwrap();
+        assert_eq!(line, None);
+        assert_eq!(tailer.position().await.unwrap(), 0);
+
+        append(" has worn off.\n");
+        let line = tailer
+            .line_buffer
+            .read_line(&mut tailer.reader)
+            .await
+            .unwrap();
+        assert_eq!(
+            line,
+            Some(&b"[Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n"[..])
+        );
+        let len = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(tailer.position().await.unwrap(), len);
+
+        // A line that's never finished is taken as it is after a while
+        append("[Fri Oct 16 12:00:01 2026] You feel");
+        let line = tailer
+            .line_buffer
+            .read_line(&mut tailer.reader)
+            .await
+            .unwrap();
+        assert_eq!(line, None);
+        tokio::time::sleep(PARTIAL_LINE_TIMEOUT).await;
+        let line = tailer
+            .line_buffer
+            .read_line(&mut tailer.reader)
+            .await
+            .unwrap();
+        assert_eq!(line, Some(&b"[Fri Oct 16 12:00:01 2026] You feel"[..]));
+        let line = tailer
+            .line_buffer
+            .read_line(&mut tailer.reader)
+            .await
+            .unwrap();
+        assert_eq!(line, None);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,684 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use quarm_audio::{AnnouncementStyle, TtsEngine};
+use quarm_config::{LateTimerPolicy, TriggerCategory};
+
+use crate::{AbortOnDrop, Announcement, EventBus, MonitorEvent, announcer};
+
+/// Timers are kept apart per character and trigger: (character, trigger index)
+pub(crate) type TimerKey = (String, usize);
+
+/// A timer that hasn't fired yet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimerInfo {
+    pub id: u64,
+    pub character: String,
+    pub pattern: String,
+    pub announcement: String,
+    pub started_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    pub remaining_secs: f64,
+}
+
+struct RunningTimer {
+    id: u64,
+    /// Pattern of the trigger that started it, for display
+    pattern: String,
+    announcement: Announcement,
+    /// Delay the timer was started with, restored by `reset`
+    delay: Duration,
+    started_at: DateTime<Utc>,
+    deadline: Instant,
+    /// Sleeps until the deadline, then speaks the announcement
+    handle: JoinHandle<()>,
+}
+
+impl RunningTimer {
+    fn is_pending(&self) -> bool {
+        !self.handle.is_finished() && self.deadline > Instant::now()
+    }
+}
+
+/// A pending timer as written to the state file, with an absolute deadline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedTimer {
+    character: String,
+    /// Index of the trigger that started it
+    #[serde(default)]
+    trigger: usize,
+    pattern: String,
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<TriggerCategory>,
+    #[serde(default)]
+    pan: i8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    speaker: Option<i64>,
+    /// Delay the timer was started with, restored by `reset`
+    delay_secs: f64,
+    deadline: DateTime<Utc>,
+}
+
+/// Contents of the timer state file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedTimers {
+    timers: Vec<SavedTimer>,
+}
+
+/// Writes snapshots of the pending timers to the state file in the background, so disk IO
+/// never holds up the monitor
+struct StateWriter {
+    snapshots: mpsc::UnboundedSender<SavedTimers>,
+    task: JoinHandle<()>,
+}
+
+/// Timed delay announcements waiting to fire, shared by the monitor and anything managing them
+/// A timer started for a character and trigger that already has one replaces it (debounce)
+#[derive(Clone)]
+pub struct Timers {
+    running: Arc<Mutex<HashMap<TimerKey, RunningTimer>>>,
+    next_id: Arc<AtomicU64>,
+    engine: TtsEngine,
+    events: EventBus,
+    /// Saves the pending timers after every change, once restored from a state file
+    state_writer: Arc<Mutex<Option<StateWriter>>>,
+}
+
+impl Timers {
+    pub(crate) fn new(engine: TtsEngine, events: EventBus) -> Self {
+        Self {
+            running: Arc::default(),
+            next_id: Arc::new(AtomicU64::new(1)),
+            engine,
+            events,
+            state_writer: Arc::default(),
+        }
+    }
+
+    /// Starts a timer, replacing any timer for the same character and trigger
+    /// Returns the new timer's id
+    pub(crate) fn schedule(
+        &self,
+        key: TimerKey,
+        pattern: String,
+        announcement: Announcement,
+        delay: Duration,
+    ) -> u64 {
+        self.start(key, pattern, announcement, delay, Instant::now() + delay)
+    }
+
+    /// Starts a timer that fires at `deadline`, which may differ from its original delay
+    fn start(
+        &self,
+        key: TimerKey,
+        pattern: String,
+        announcement: Announcement,
+        delay: Duration,
+        deadline: Instant,
+    ) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let mut running = self.running.lock().unwrap();
+        // Forget timers that have finished speaking
+        running.retain(|_, timer| !timer.handle.is_finished());
+        if let Some(old) = running.remove(&key) {
+            old.handle.abort();
+            println!("Cancelled existing timer for {}: '{}'", key.0, old.pattern);
+            self.publish_cancelled(&key, &old);
+        }
+
+        println!(
+            "Scheduled timer for {}: '{}' -> '{}' ({}s)",
+            key.0,
+            pattern,
+            announcement.text,
+            deadline
+                .saturating_duration_since(Instant::now())
+                .as_secs_f64()
+        );
+        let timer = RunningTimer {
+            id,
+            handle: self.spawn(&key, &pattern, id, &announcement, deadline),
+            pattern,
+            announcement,
+            delay,
+            started_at: Utc::now(),
+            deadline,
+        };
+        self.publish_scheduled(&key, &timer);
+        running.insert(key, timer);
+        self.save(&running);
+        id
+    }
+
+    /// Restarts the timers saved in a state file, then keeps the file up to date
+    /// Timers that ran out in the meantime are dropped or announced right away, per `late`
+    pub(crate) fn restore(&self, path: &Path, late: LateTimerPolicy) -> Result<()> {
+        let (snapshots, pending) = mpsc::unbounded_channel();
+        let task = tokio::spawn(write_snapshots(
+            path.to_path_buf(),
+            pending,
+            self.events.clone(),
+        ));
+        *self.state_writer.lock().unwrap() = Some(StateWriter { snapshots, task });
+
+        let saved: SavedTimers = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .context(format!("Failed to parse timer state: {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => {
+                return Err(e).context(format!("Failed to read timer state: {}", path.display()));
+            }
+        };
+
+        let now = Utc::now();
+        for timer in saved.timers {
+            let remaining = (timer.deadline - now).to_std().unwrap_or(Duration::ZERO);
+            if remaining.is_zero() && late == LateTimerPolicy::Drop {
+                println!(
+                    "Dropped timer for {}: '{}' (ran out at {})",
+                    timer.character, timer.pattern, timer.deadline
+                );
+                continue;
+            }
+
+            let announcement = Announcement {
+                text: timer.text,
+                style: AnnouncementStyle {
+                    category: timer.category,
+                    pan: timer.pan,
+                    speaker: timer.speaker,
+                },
+            };
+            self.start(
+                (timer.character, timer.trigger),
+                timer.pattern,
+                announcement,
+                Duration::try_from_secs_f64(timer.delay_secs).unwrap_or(remaining),
+                Instant::now() + remaining,
+            );
+        }
+
+        // Leaves out the dropped timers
+        self.save(&self.running.lock().unwrap());
+        Ok(())
+    }
+
+    /// Lists the timers that haven't fired yet, soonest first
+    pub fn list(&self) -> Vec<TimerInfo> {
+        let now = Instant::now();
+        let mut timers: Vec<TimerInfo> = self
+            .running
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, timer)| timer.is_pending())
+            .map(|((character, _), timer)| {
+                let remaining = timer.deadline.saturating_duration_since(now);
+                TimerInfo {
+                    id: timer.id,
+                    character: character.clone(),
+                    pattern: timer.pattern.clone(),
+                    announcement: timer.announcement.text.clone(),
+                    started_at: timer.started_at,
+                    deadline: wall_clock(remaining),
+                    remaining_secs: remaining.as_secs_f64(),
+                }
+            })
+            .collect();
+        timers.sort_by(|a, b| a.remaining_secs.total_cmp(&b.remaining_secs));
+        timers
+    }
+
+    /// Stops a timer before it fires; returns false if no such timer is pending
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut running = self.running.lock().unwrap();
+        let Some(key) = find_pending(&running, id) else {
+            return false;
+        };
+        if let Some(timer) = running.remove(&key) {
+            timer.handle.abort();
+            self.publish_cancelled(&key, &timer);
+        }
+        self.save(&running);
+        true
+    }
+
+    /// Stops every pending timer, returning how many were cancelled
+    pub fn cancel_all(&self) -> usize {
+        let mut running = self.running.lock().unwrap();
+        let mut cancelled = 0;
+        for (key, timer) in running.drain() {
+            if timer.is_pending() {
+                cancelled += 1;
+                self.publish_cancelled(&key, &timer);
+            }
+            timer.handle.abort();
+        }
+        self.save(&running);
+        cancelled
+    }
+
+    /// Pushes a pending timer's deadline back; returns false if no such timer is pending
+    pub fn extend(&self, id: u64, by: Duration) -> bool {
+        self.restart(id, |timer| timer.deadline + by)
+    }
+
+    /// Restarts a pending timer from its original delay; returns false if no such timer is
+    /// pending
+    pub fn reset(&self, id: u64) -> bool {
+        self.restart(id, |timer| Instant::now() + timer.delay)
+    }
+
+    /// Waits for every timer to fire and finish speaking
+    /// Timers still waiting when this is cancelled are stopped
+    pub(crate) async fn wait_all(&self) {
+        let mut handles = AbortOnDrop(
+            self.running
+                .lock()
+                .unwrap()
+                .drain()
+                .map(|(_, timer)| timer.handle)
+                .collect(),
+        );
+        for handle in &mut handles.0 {
+            let _ = handle.await;
+        }
+    }
+
+    /// Stops every timer without cancelling it, leaving the state file as it is so pending
+    /// timers are picked up again on the next start
+    pub(crate) async fn suspend_all(&self) {
+        // Detach from the state file first, so nothing saves over it from here on
+        self.close_state_file().await;
+        for (_, timer) in self.running.lock().unwrap().drain() {
+            timer.handle.abort();
+        }
+    }
+
+    /// Stops saving to the state file, once the changes made so far are written
+    pub(crate) async fn close_state_file(&self) {
+        let writer = self.state_writer.lock().unwrap().take();
+        if let Some(StateWriter { snapshots, task }) = writer {
+            drop(snapshots);
+            let _ = task.await;
+        }
+    }
+
+    /// Moves a pending timer to a new deadline, keeping its id
+    fn restart(&self, id: u64, deadline: impl FnOnce(&RunningTimer) -> Instant) -> bool {
+        let mut running = self.running.lock().unwrap();
+        let Some(key) = find_pending(&running, id) else {
+            return false;
+        };
+        let Some(timer) = running.get_mut(&key) else {
+            return false;
+        };
+
+        timer.handle.abort();
+        timer.deadline = deadline(timer);
+        timer.handle = self.spawn(
+            &key,
+            &timer.pattern,
+            id,
+            &timer.announcement,
+            timer.deadline,
+        );
+        self.publish_scheduled(&key, timer);
+        self.save(&running);
+        true
+    }
+
+    fn spawn(
+        &self,
+        key: &TimerKey,
+        pattern: &str,
+        id: u64,
+        announcement: &Announcement,
+        deadline: Instant,
+    ) -> JoinHandle<()> {
+        let character = key.0.clone();
+        let pattern = pattern.to_string();
+        let announcement = announcement.clone();
+        let timers = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep_until(deadline).await;
+            // No longer pending, so this leaves it out of the saved timers
+            timers.save(&timers.running.lock().unwrap());
+            timers.events.publish(MonitorEvent::TimerFired {
+                id,
+                character,
+                pattern,
+                announcement: announcement.text.clone(),
+            });
+            announcer::speak(&timers.engine, &announcement, &timers.events).await;
+        })
+    }
+
+    /// Queues the pending timers to be written to the state file, if there is one
+    fn save(&self, running: &HashMap<TimerKey, RunningTimer>) {
+        let state_writer = self.state_writer.lock().unwrap();
+        let Some(writer) = state_writer.as_ref() else {
+            return;
+        };
+
+        let now = Instant::now();
+        let saved = SavedTimers {
+            timers: running
+                .iter()
+                .filter(|(_, timer)| timer.is_pending())
+                .map(|((character, trigger), timer)| SavedTimer {
+                    character: character.clone(),
+                    trigger: *trigger,
+                    pattern: timer.pattern.clone(),
+                    text: timer.announcement.text.clone(),
+                    category: timer.announcement.style.category,
+                    pan: timer.announcement.style.pan,
+                    speaker: timer.announcement.style.speaker,
+                    delay_secs: timer.delay.as_secs_f64(),
+                    deadline: wall_clock(timer.deadline.saturating_duration_since(now)),
+                })
+                .collect(),
+        };
+        // The writer only stops once detached from here, so this can't fail
+        let _ = writer.snapshots.send(saved);
+    }
+
+    fn publish_scheduled(&self, key: &TimerKey, timer: &RunningTimer) {
+        let remaining = timer.deadline.saturating_duration_since(Instant::now());
+        self.events.publish(MonitorEvent::TimerScheduled {
+            id: timer.id,
+            character: key.0.clone(),
+            pattern: timer.pattern.clone(),
+            announcement: timer.announcement.text.clone(),
+            delay_secs: remaining.as_secs_f64(),
+            deadline: wall_clock(remaining),
+        });
+    }
+
+    fn publish_cancelled(&self, key: &TimerKey, timer: &RunningTimer) {
+        self.events.publish(MonitorEvent::TimerCancelled {
+            id: timer.id,
+            character: key.0.clone(),
+            pattern: timer.pattern.clone(),
+        });
+    }
+}
+
+/// Writes each snapshot of the pending timers as it comes, skipping straight to the latest
+/// when they come faster than they're written
+async fn write_snapshots(
+    path: PathBuf,
+    mut snapshots: mpsc::UnboundedReceiver<SavedTimers>,
+    events: EventBus,
+) {
+    while let Some(mut saved) = snapshots.recv().await {
+        while let Ok(newer) = snapshots.try_recv() {
+            saved = newer;
+        }
+        let path = path.clone();
+        let result = tokio::task::spawn_blocking(move || write_state(&path, &saved))
+            .await
+            .context("Failed to spawn blocking task for saving timers")
+            .and_then(|written| written);
+        if let Err(e) = result {
+            eprintln!("Failed to save timers: {:#}", e);
+            events.error(format!("Failed to save timers: {:#}", e));
+        }
+    }
+}
+
+fn write_state(path: &Path, saved: &SavedTimers) -> Result<()> {
+    let json = serde_json::to_string_pretty(saved).context("Failed to serialize timers")?;
+    crate::replace_file(path, json.as_bytes())
+}
+
+fn find_pending(running: &HashMap<TimerKey, RunningTimer>, id: u64) -> Option<TimerKey> {
+    running
+        .iter()
+        .find(|(_, timer)| timer.id == id && timer.is_pending())
+        .map(|(key, _)| key.clone())
+}
+
+/// Converts a time remaining into the wall-clock time it runs out
+fn wall_clock(remaining: Duration) -> DateTime<Utc> {
+    Utc::now() + TimeDelta::from_std(remaining).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quarm_audio::AnnouncementStyle;
+
+    fn test_timers() -> Timers {
+        let engine = TtsEngine::new_mock().expect("Failed to create mock TTS engine");
+        Timers::new(engine, EventBus::default())
+    }
+
+    fn key(character: &str) -> TimerKey {
+        (character.to_string(), 0)
+    }
+
+    fn pattern() -> String {
+        "flesh begins to liquefy".to_string()
+    }
+
+    fn announcement() -> Announcement {
+        Announcement {
+            text: "go back in".to_string(),
+            style: AnnouncementStyle::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_extend_and_reset() {
+        let timers = test_timers();
+        let id = timers.schedule(
+            key("Soandso"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(60),
+        );
+        timers.schedule(
+            key("Otherguy"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(30),
+        );
+
+        let listed = timers.list();
+        assert_eq!(listed.len(), 2);
+        // Soonest first
+        assert_eq!(listed[0].character, "Otherguy");
+        assert_eq!(listed[1].id, id);
+        assert_eq!(listed[1].announcement, "go back in");
+        assert!(listed[1].remaining_secs > 59.0 && listed[1].remaining_secs <= 60.0);
+
+        assert!(timers.extend(id, Duration::from_secs(30)));
+        let soandso = timers.list().into_iter().find(|t| t.id == id).unwrap();
+        assert!(soandso.remaining_secs > 89.0 && soandso.remaining_secs <= 90.0);
+
+        assert!(timers.reset(id));
+        let soandso = timers.list().into_iter().find(|t| t.id == id).unwrap();
+        assert!(soandso.remaining_secs > 59.0 && soandso.remaining_secs <= 60.0);
+
+        assert!(!timers.extend(999, Duration::from_secs(1)));
+        timers.cancel_all();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_id_and_all() {
+        let timers = test_timers();
+        let mut events = timers.events.subscribe();
+        let first = timers.schedule(
+            key("Soandso"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(60),
+        );
+        timers.schedule(
+            key("Otherguy"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(60),
+        );
+        timers.schedule(
+            key("Thirdguy"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(60),
+        );
+
+        assert!(timers.cancel(first));
+        assert!(!timers.cancel(first));
+        assert_eq!(timers.list().len(), 2);
+
+        assert_eq!(timers.cancel_all(), 2);
+        assert!(timers.list().is_empty());
+
+        let cancelled = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|event| matches!(event, MonitorEvent::TimerCancelled { .. }))
+            .count();
+        assert_eq!(cancelled, 3);
+    }
+
+    fn state_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("quarm_timers_{}_{}.json", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_timers_survive_restart() {
+        let path = state_file("restart");
+        let _ = std::fs::remove_file(&path);
+
+        // Nothing to restore the first time
+        let before = test_timers();
+        before.restore(&path, LateTimerPolicy::Drop).unwrap();
+        let mut danger = announcement();
+        danger.style.category = Some(TriggerCategory::Danger);
+        danger.style.pan = -50;
+        before.schedule(
+            key("Soandso"),
+            pattern(),
+            danger.clone(),
+            Duration::from_secs(60),
+        );
+        let id = before.schedule(
+            key("Otherguy"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(60),
+        );
+        before.cancel(id);
+        before.close_state_file().await;
+
+        // A new monitor picks up where the old one left off
+        let after = test_timers();
+        after.restore(&path, LateTimerPolicy::Drop).unwrap();
+        let restored = after.list();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].character, "Soandso");
+        assert!(restored[0].remaining_secs > 58.0 && restored[0].remaining_secs <= 60.0);
+        {
+            let running = after.running.lock().unwrap();
+            let timer = running.values().next().unwrap();
+            assert_eq!(timer.announcement, danger);
+            assert_eq!(timer.delay, Duration::from_secs(60));
+        }
+
+        before.cancel_all();
+        after.cancel_all();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_suspended_timers_stay_saved() {
+        let path = state_file("suspend");
+        let _ = std::fs::remove_file(&path);
+
+        let before = test_timers();
+        before.restore(&path, LateTimerPolicy::Drop).unwrap();
+        before.schedule(
+            key("Soandso"),
+            pattern(),
+            announcement(),
+            Duration::from_secs(60),
+        );
+        let mut events = before.events.subscribe();
+        before.suspend_all().await;
+        assert!(before.list().is_empty());
+        assert!(events.try_recv().is_err());
+
+        let after = test_timers();
+        after.restore(&path, LateTimerPolicy::Drop).unwrap();
+        assert_eq!(after.list().len(), 1);
+
+        after.cancel_all();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_late_timer_policy() {
+        let expired = SavedTimers {
+            timers: vec![SavedTimer {
+                character: "Soandso".to_string(),
+                trigger: 0,
+                pattern: "flesh begins to liquefy".to_string(),
+                text: "go back in".to_string(),
+                category: None,
+                pan: 0,
+                speaker: None,
+                delay_secs: 22.0,
+                deadline: Utc::now() - TimeDelta::seconds(10),
+            }],
+        };
+
+        // Dropped, and removed from the file
+        let path = state_file("late_drop");
+        write_state(&path, &expired).unwrap();
+        let timers = test_timers();
+        let mut events = timers.events.subscribe();
+        timers.restore(&path, LateTimerPolicy::Drop).unwrap();
+        assert!(timers.list().is_empty());
+        assert!(events.try_recv().is_err());
+        timers.close_state_file().await;
+        let saved: SavedTimers =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(saved.timers.is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        // Announced right away
+        let path = state_file("late_announce");
+        write_state(&path, &expired).unwrap();
+        let timers = test_timers();
+        let mut events = timers.events.subscribe();
+        timers.restore(&path, LateTimerPolicy::Announce).unwrap();
+        timers.wait_all().await;
+        let fired = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|event| matches!(event, MonitorEvent::TimerFired { .. }))
+            .count();
+        assert_eq!(fired, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fired_timers_are_not_listed() {
+        let timers = test_timers();
+        let id = timers.schedule(key("Soandso"), pattern(), announcement(), Duration::ZERO);
+        timers.wait_all().await;
+
+        assert!(timers.list().is_empty());
+        assert!(!timers.cancel(id));
+    }
+}
@@ -0,0 +1,226 @@
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use inotify::{EventMask, EventStream, Inotify, WatchMask};
+
+use crate::{IDLE_RETRY_DELAY, LOG_FILE_PREFIX};
+
+// Size of the buffer inotify events are read into
+#[cfg(target_os = "linux")]
+const EVENT_BUFFER_SIZE: usize = 4096;
+
+/// Why a wait on the watcher ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Wake {
+    /// A log file was written to (or may have been, when polling)
+    Modified,
+    /// A log file was created, deleted or renamed, so the set of logs may have changed
+    LogsChanged,
+    /// Nothing happened before the timeout
+    TimedOut,
+}
+
+/// Wakes the monitor when log files change, instead of it polling on a fixed delay
+/// Uses inotify on Linux, and polling every `IDLE_RETRY_DELAY` elsewhere or when
+/// inotify isn't available
+pub(crate) enum LogWatcher {
+    #[cfg(target_os = "linux")]
+    Inotify(EventStream<Vec<u8>>),
+    Polling,
+}
+
+impl LogWatcher {
+    /// Watches a directory's log files, falling back to polling if that fails
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub(crate) fn new(directory: &Path) -> Self {
+        #[cfg(target_os = "linux")]
+        match watch_directory(directory) {
+            Ok(stream) => return Self::Inotify(stream),
+            Err(e) => eprintln!(
+                "Failed to watch {:?} for changes, polling instead: {}",
+                directory, e
+            ),
+        }
+
+        Self::Polling
+    }
+
+    /// Waits until a log file changes, or at most `timeout`
+    /// When polling, returns `Modified` after `IDLE_RETRY_DELAY` so the caller checks for new lines
+    pub(crate) async fn wait(&mut self, timeout: Duration) -> Wake {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Inotify(stream) => match wait_for_log_event(stream, timeout).await {
+                Ok(wake) => wake,
+                Err(e) => {
+                    eprintln!("File watcher failed, polling instead: {}", e);
+                    *self = Self::Polling;
+                    Wake::LogsChanged
+                }
+            },
+            Self::Polling => {
+                if timeout < IDLE_RETRY_DELAY {
+                    tokio::time::sleep(timeout).await;
+                    Wake::TimedOut
+                } else {
+                    tokio::time::sleep(IDLE_RETRY_DELAY).await;
+                    Wake::Modified
+                }
+            }
+        }
+    }
+
+    /// Waits until a log file is created, deleted or renamed, or at most `timeout`
+    /// When polling, there's no telling, so this waits out the timeout and returns `LogsChanged`
+    pub(crate) async fn wait_for_logs_changed(&mut self, timeout: Duration) -> Wake {
+        if matches!(self, Self::Polling) {
+            tokio::time::sleep(timeout).await;
+            return Wake::LogsChanged;
+        }
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            match self.wait(remaining).await {
+                Wake::Modified => continue,
+                wake => return wake,
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn watch_directory(directory: &Path) -> std::io::Result<EventStream<Vec<u8>>> {
+    let inotify = Inotify::init()?;
+    inotify.watches().add(
+        directory,
+        WatchMask::MODIFY
+            | WatchMask::CREATE
+            | WatchMask::DELETE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO,
+    )?;
+    inotify.into_event_stream(vec![0; EVENT_BUFFER_SIZE])
+}
+
+/// Waits for the next event on an eqlog_* file, ignoring other files in the directory
+#[cfg(target_os = "linux")]
+async fn wait_for_log_event(
+    stream: &mut EventStream<Vec<u8>>,
+    timeout: Duration,
+) -> std::io::Result<Wake> {
+    use futures_core::Stream;
+    use std::pin::Pin;
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let next = std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx));
+        let event = match tokio::time::timeout_at(deadline, next).await {
+            Err(_) => return Ok(Wake::TimedOut),
+            Ok(None) => {
+                return Err(std::io::Error::other("inotify event stream ended"));
+            }
+            Ok(Some(event)) => event?,
+        };
+
+        let is_log = event
+            .name
+            .is_some_and(|name| name.to_string_lossy().starts_with(LOG_FILE_PREFIX));
+        // Overflowed queues drop events, so treat them as a change to anything
+        if event.mask.contains(EventMask::Q_OVERFLOW) {
+            return Ok(Wake::LogsChanged);
+        }
+        if !is_log {
+            continue;
+        }
+
+        return Ok(if event.mask.contains(EventMask::MODIFY) {
+            Wake::Modified
+        } else {
+            Wake::LogsChanged
+        });
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::Instant;
+
+    // How long after the wait starts the log is written to
+    const WRITE_DELAY: Duration = Duration::from_millis(10);
+
+    /// Consumes events queued by earlier writes
+    async fn drain(watcher: &mut LogWatcher) {
+        while watcher.wait(Duration::from_millis(20)).await != Wake::TimedOut {}
+    }
+
+    /// Measures how long after a log write the watcher wakes up
+    async fn wake_latency(watcher: &mut LogWatcher, log: &Path) -> Duration {
+        let log = log.to_path_buf();
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(WRITE_DELAY).await;
+            std::fs::OpenOptions::new()
+                .append(true)
+                .open(&log)
+                .unwrap()
+                .write_all(b"[Fri Oct 16 12:00:00 2026] Your charm spell has worn off.\n")
+                .unwrap();
+            Instant::now()
+        });
+
+        assert_ne!(watcher.wait(Duration::from_secs(5)).await, Wake::TimedOut);
+        let woke = Instant::now();
+        woke.saturating_duration_since(writer.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_inotify_wakes_faster_than_polling() {
+        let dir = std::env::temp_dir().join(format!("quarm_watcher_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("eqlog_Soandso_pq.proj.txt");
+        std::fs::write(&log, "").unwrap();
+
+        let mut watcher = LogWatcher::new(&dir);
+        if matches!(watcher, LogWatcher::Polling) {
+            eprintln!("inotify unavailable, skipping latency comparison");
+            std::fs::remove_dir_all(&dir).unwrap();
+            return;
+        }
+
+        // Creating a log is reported as a change to the set of logs
+        std::fs::write(dir.join("eqlog_Otherguy_pq.proj.txt"), "").unwrap();
+        assert_eq!(
+            watcher.wait(Duration::from_secs(5)).await,
+            Wake::LogsChanged
+        );
+        // Other files are ignored
+        drain(&mut watcher).await;
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        assert_eq!(
+            watcher.wait(Duration::from_millis(50)).await,
+            Wake::TimedOut
+        );
+
+        let mut polling = LogWatcher::Polling;
+        let mut inotify_total = Duration::ZERO;
+        let mut polling_total = Duration::ZERO;
+        for _ in 0..5 {
+            drain(&mut watcher).await;
+            inotify_total += wake_latency(&mut watcher, &log).await;
+            polling_total += wake_latency(&mut polling, &log).await;
+        }
+        println!(
+            "Average wake latency: inotify {:?}, polling {:?}",
+            inotify_total / 5,
+            polling_total / 5
+        );
+
+        // Polling only notices a write made 10 ms into its 50 ms sleep once the sleep ends
+        assert!(inotify_total < polling_total);
+        assert!(polling_total / 5 >= IDLE_RETRY_DELAY - WRITE_DELAY * 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
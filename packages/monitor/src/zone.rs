@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use encoding_rs::Encoding;
+
+use crate::game_event::zone_entered;
+use crate::history;
+use crate::log_line::LogLine;
+
+/// Shared handle to the zones the monitored characters are in
+/// Cloning shares the same underlying zones
+#[derive(Debug, Clone, Default)]
+pub struct ZoneTracker {
+    state: Arc<Mutex<ZoneState>>,
+}
+
+#[derive(Debug, Default)]
+struct ZoneState {
+    /// Known zone of each character
+    by_character: HashMap<String, String>,
+    /// Character whose zone was set most recently
+    latest: Option<String>,
+}
+
+impl ZoneTracker {
+    /// Returns the zone of the character whose zone changed most recently, if known
+    pub fn current(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        let character = state.latest.as_ref()?;
+        state.by_character.get(character).cloned()
+    }
+
+    /// Returns the zone of a character, if known
+    pub fn for_character(&self, character: &str) -> Option<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .by_character
+            .get(character)
+            .cloned()
+    }
+
+    pub(crate) fn set(&self, character: &str, zone: Option<String>) {
+        let mut state = self.state.lock().unwrap();
+        match zone {
+            Some(zone) => {
+                state.by_character.insert(character.to_string(), zone);
+                state.latest = Some(character.to_string());
+            }
+            None => {
+                state.by_character.remove(character);
+            }
+        }
+    }
+}
+
+/// Best-effort guess of a character's zone from the last "You have entered X." line
+/// written before `before`, the offset monitoring starts reading the log at
+pub(crate) fn guess_zone_from_history(
+    path: &Path,
+    before: u64,
+    encoding: &'static Encoding,
+) -> Result<Option<String>> {
+    let mut zone = None;
+    history::scan_back(path, before, |raw, _| {
+        let line = LogLine::decode(raw, encoding);
+        zone = zone_entered(&line.body).map(str::to_string);
+        zone.is_some()
+    })?;
+    Ok(zone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::WINDOWS_1252;
+
+    #[test]
+    fn test_guess_zone_from_history() {
+        let path =
+            std::env::temp_dir().join(format!("quarm_zone_history_{}.txt", std::process::id()));
+
+        let mut log = String::from(
+            "[Fri Oct 16 11:00:00 2026] You have entered The Plane of Hate.\n\
+             [Fri Oct 16 11:30:00 2026] You have entered The Plane of Fear.\n",
+        );
+        // Push the zone line back across several chunk boundaries
+        while (log.len() as u64) < history::HISTORY_CHUNK_SIZE * 3 {
+            log.push_str(
+                "[Fri Oct 16 12:00:00 2026] a fire beetle bites YOU for 12 points of damage.\n",
+            );
+        }
+        std::fs::write(&path, &log).unwrap();
+        let len = log.len() as u64;
+        assert_eq!(
+            guess_zone_from_history(&path, len, WINDOWS_1252)
+                .unwrap()
+                .as_deref(),
+            Some("The Plane of Fear")
+        );
+        // Only lines before the offset count
+        let second_line = log.find("[Fri Oct 16 11:30").unwrap() as u64;
+        assert_eq!(
+            guess_zone_from_history(&path, second_line, WINDOWS_1252)
+                .unwrap()
+                .as_deref(),
+            Some("The Plane of Hate")
+        );
+
+        std::fs::write(
+            &path,
+            "[Fri Oct 16 12:00:00 2026] You feel less charming.\n",
+        )
+        .unwrap();
+        assert_eq!(
+            guess_zone_from_history(&path, 51, WINDOWS_1252).unwrap(),
+            None
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tracker_clones_share_zone() {
+        let tracker = ZoneTracker::default();
+        let handle = tracker.clone();
+        assert_eq!(handle.current(), None);
+
+        tracker.set("Soandso", Some("Nektulos Forest".to_string()));
+        assert_eq!(handle.current().as_deref(), Some("Nektulos Forest"));
+
+        // Each character keeps its own zone; current follows the latest change
+        tracker.set("Otherguy", Some("The Plane of Fear".to_string()));
+        assert_eq!(handle.current().as_deref(), Some("The Plane of Fear"));
+        assert_eq!(
+            handle.for_character("Soandso").as_deref(),
+            Some("Nektulos Forest")
+        );
+
+        tracker.set("Otherguy", None);
+        assert_eq!(handle.for_character("Otherguy"), None);
+        assert_eq!(handle.current(), None);
+    }
+}
@@ -1,22 +1,26 @@
+use std::path::PathBuf;
 use std::sync::atomic::Ordering;
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tokio::task::JoinHandle;
 
 use quarm_audio::TtsEngine;
 use quarm_config::Config;
-use quarm_monitor::LogMonitor;
+use quarm_monitor::{
+    CancellationToken, DryRunReport, LagStats, LagTracker, LogMonitor, ReplayOptions, ReplaySpeed,
+    TimerInfo, Timers, announcement_variants, dry_run, parse_replay_time,
+};
 
+use crate::events::forward_events;
 use crate::state::AppState;
 
+/// How long stopping waits for the monitor to wind down before aborting it
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Load configuration from a JSON file
 #[tauri::command]
 pub async fn load_config(state: State<'_, AppState>) -> Result<Config, String> {
-    // Get directory containing the executable
-    let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| "Failed to get executable directory".to_string())?;
-    let config_path = exe_dir.join("config.json");
+    let config_path = file_beside_executable("config.json")?;
 
     println!("Loading config from: {:?}", config_path);
 
@@ -33,13 +37,7 @@ pub async fn load_config(state: State<'_, AppState>) -> Result<Config, String> {
 /// Save configuration to a JSON file
 #[tauri::command]
 pub async fn save_config(config: Config, state: State<'_, AppState>) -> Result<(), String> {
-    // Get directory containing the executable
-    let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| "Failed to get executable directory".to_string())?;
-    let config_path = exe_dir.join("config.json");
+    let config_path = file_beside_executable("config.json")?;
 
     config
         .save(config_path.to_string_lossy().as_ref())
@@ -72,13 +70,14 @@ pub async fn init_tts(model_path: String, state: State<'_, AppState>) -> Result<
     // Pre-cache announcements if config is loaded
     let mut engine = engine;
     if let Some(config) = state.config.lock().await.as_ref() {
-        let announcements: Vec<String> = config
-            .messages
-            .iter()
-            .map(|m| m.announcement().to_string())
-            .collect();
+        engine.set_lexicon(config.lexicon.clone());
         engine
-            .precache(announcements)
+            .set_audio_settings(config.audio.clone())
+            .await
+            .map_err(|e| format!("Failed to apply audio settings: {}", e))?;
+        // Includes each character profile's voice and prefix
+        engine
+            .precache_styled(announcement_variants(config))
             .await
             .map_err(|e| format!("Failed to precache announcements: {}", e))?;
     }
@@ -105,15 +104,16 @@ pub async fn test_announcement(text: String, state: State<'_, AppState>) -> Resu
 }
 
 /// Start monitoring log files
+/// Matches, announcements, timers and log changes are emitted to the UI as `monitor-event`
 #[tauri::command]
-pub async fn start_monitoring(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn start_monitoring(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     // Check if already monitoring
     if state.is_monitoring.load(Ordering::SeqCst) {
         return Err("Already monitoring".to_string());
     }
 
     // Get config and TTS engine
-    let config = state
+    let mut config = state
         .config
         .lock()
         .await
@@ -121,6 +121,17 @@ pub async fn start_monitoring(state: State<'_, AppState>) -> Result<(), String>
         .cloned()
         .ok_or_else(|| "No configuration loaded".to_string())?;
 
+    // Keep pending timers across restarts, next to the config unless configured otherwise
+    if config.monitor.timer_state_file.is_none() {
+        let state_file = file_beside_executable("timers.json")?;
+        config.monitor.timer_state_file = Some(state_file.to_string_lossy().into_owned());
+    }
+    // Remember where reading stopped in each log, so monitoring can resume there
+    if config.monitor.bookmark_file.is_none() {
+        let bookmark_file = file_beside_executable("bookmarks.json")?;
+        config.monitor.bookmark_file = Some(bookmark_file.to_string_lossy().into_owned());
+    }
+
     let tts_engine = state
         .tts_engine
         .lock()
@@ -130,12 +141,19 @@ pub async fn start_monitoring(state: State<'_, AppState>) -> Result<(), String>
         .ok_or_else(|| "TTS engine not initialized".to_string())?;
 
     // Create monitor
-    let monitor = LogMonitor::new(config, tts_engine);
+    let monitor = LogMonitor::new(config, tts_engine)
+        .map_err(|e| format!("Failed to create monitor: {}", e))?;
+    *state.zone_tracker.lock().await = Some(monitor.zone_tracker());
+    *state.timers.lock().await = Some(monitor.timers());
+    *state.lag.lock().await = Some(monitor.lag());
+    *state.event_forwarder.lock().await = Some(forward_events(app, &monitor.events()));
 
     // Spawn monitoring task
+    let shutdown = CancellationToken::new();
+    *state.shutdown.lock().await = Some(shutdown.clone());
     let is_monitoring = Arc::clone(&state.is_monitoring);
     let handle = tokio::spawn(async move {
-        if let Err(e) = monitor.start_monitoring().await {
+        if let Err(e) = monitor.start_monitoring(shutdown).await {
             eprintln!("Monitoring error: {}", e);
         }
         is_monitoring.store(false, Ordering::SeqCst);
@@ -148,7 +166,88 @@ pub async fn start_monitoring(state: State<'_, AppState>) -> Result<(), String>
     Ok(())
 }
 
+/// Replay a recorded log file through the triggers, in place of live monitoring
+/// `speed` is a multiplier like "10x" or "instant"; `start`/`end` are "YYYY-MM-DD HH:MM:SS"
+/// Stopped with `stop_monitoring`, like a live session, and emits the same events
+#[tauri::command]
+pub async fn start_replay(
+    app: AppHandle,
+    log_path: String,
+    speed: String,
+    start: Option<String>,
+    end: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if state.is_monitoring.load(Ordering::SeqCst) {
+        return Err("Already monitoring".to_string());
+    }
+
+    let options = ReplayOptions {
+        speed: speed.parse::<ReplaySpeed>()?,
+        start: start.as_deref().map(parse_replay_time).transpose()?,
+        end: end.as_deref().map(parse_replay_time).transpose()?,
+    };
+
+    let config = state
+        .config
+        .lock()
+        .await
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| "No configuration loaded".to_string())?;
+
+    let tts_engine = state
+        .tts_engine
+        .lock()
+        .await
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| "TTS engine not initialized".to_string())?;
+
+    let monitor = LogMonitor::new(config, tts_engine)
+        .map_err(|e| format!("Failed to create monitor: {}", e))?;
+    *state.zone_tracker.lock().await = Some(monitor.zone_tracker());
+    *state.timers.lock().await = Some(monitor.timers());
+    *state.event_forwarder.lock().await = Some(forward_events(app, &monitor.events()));
+
+    let shutdown = CancellationToken::new();
+    *state.shutdown.lock().await = Some(shutdown.clone());
+    let is_monitoring = Arc::clone(&state.is_monitoring);
+    let handle = tokio::spawn(async move {
+        let log_path = std::path::Path::new(&log_path);
+        if let Err(e) = monitor.replay(log_path, &options, shutdown).await {
+            eprintln!("Replay error: {}", e);
+        }
+        is_monitoring.store(false, Ordering::SeqCst);
+    });
+
+    *state.monitor_handle.lock().await = Some(handle);
+    state.is_monitoring.store(true, Ordering::SeqCst);
+
+    Ok(())
+}
+
+/// Report which triggers would have fired over a recorded log file, without playing audio
+#[tauri::command]
+pub async fn dry_run_report(
+    log_path: String,
+    state: State<'_, AppState>,
+) -> Result<DryRunReport, String> {
+    let config = state
+        .config
+        .lock()
+        .await
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| "No configuration loaded".to_string())?;
+
+    dry_run(&config, std::path::Path::new(&log_path))
+        .await
+        .map_err(|e| format!("Failed to analyze log: {}", e))
+}
+
 /// Stop monitoring log files
+/// Waits for the monitor to drop queued announcements and stop its timers
 #[tauri::command]
 pub async fn stop_monitoring(state: State<'_, AppState>) -> Result<(), String> {
     // Check if monitoring
@@ -156,9 +255,20 @@ pub async fn stop_monitoring(state: State<'_, AppState>) -> Result<(), String> {
         return Err("Not currently monitoring".to_string());
     }
 
-    // Abort the monitoring task
+    // Ask the monitoring task to stop, aborting it if it takes too long
+    if let Some(shutdown) = state.shutdown.lock().await.take() {
+        shutdown.cancel();
+    }
     if let Some(handle) = state.monitor_handle.lock().await.take() {
-        handle.abort();
+        stop_task(handle).await;
+    }
+    *state.zone_tracker.lock().await = None;
+    *state.timers.lock().await = None;
+    *state.lag.lock().await = None;
+
+    // With the monitor gone the forwarder ends by itself, once the last events are sent
+    if let Some(forwarder) = state.event_forwarder.lock().await.take() {
+        stop_task(forwarder).await;
     }
 
     // Clear flag
@@ -173,5 +283,103 @@ pub async fn get_monitoring_status(state: State<'_, AppState>) -> Result<bool, S
     Ok(state.is_monitoring.load(Ordering::SeqCst))
 }
 
+/// Get the zone a monitored character is in, if known
+/// Without a character, returns the zone of whichever character zoned most recently
+#[tauri::command]
+pub async fn get_current_zone(
+    character: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    Ok(state
+        .zone_tracker
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|tracker| match character {
+            Some(character) => tracker.for_character(&character),
+            None => tracker.current(),
+        }))
+}
+
+/// Get how far behind the logs live monitoring is, if it's running
+#[tauri::command]
+pub async fn get_lag_stats(state: State<'_, AppState>) -> Result<Option<LagStats>, String> {
+    Ok(state.lag.lock().await.as_ref().map(LagTracker::stats))
+}
+
+/// List the running monitor's timers that haven't fired yet, soonest first
+#[tauri::command]
+pub async fn list_timers(state: State<'_, AppState>) -> Result<Vec<TimerInfo>, String> {
+    Ok(state
+        .timers
+        .lock()
+        .await
+        .as_ref()
+        .map(Timers::list)
+        .unwrap_or_default())
+}
+
+/// Cancel a timer before it fires; false if it already fired or was cancelled
+#[tauri::command]
+pub async fn cancel_timer(id: u64, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(running_timers(&state).await?.cancel(id))
+}
+
+/// Cancel every pending timer, returning how many were cancelled
+#[tauri::command]
+pub async fn cancel_all_timers(state: State<'_, AppState>) -> Result<usize, String> {
+    Ok(running_timers(&state).await?.cancel_all())
+}
+
+/// Push a timer's deadline back by some seconds; false if it already fired or was cancelled
+#[tauri::command]
+pub async fn extend_timer(
+    id: u64,
+    seconds: u64,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    Ok(running_timers(&state)
+        .await?
+        .extend(id, Duration::from_secs(seconds)))
+}
+
+/// Restart a timer from its original delay; false if it already fired or was cancelled
+#[tauri::command]
+pub async fn reset_timer(id: u64, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(running_timers(&state).await?.reset(id))
+}
+
+/// Path of a file in the directory containing the executable
+fn file_beside_executable(name: &str) -> Result<PathBuf, String> {
+    let exe_path =
+        std::env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
+    let exe_dir = exe_path
+        .parent()
+        .ok_or_else(|| "Failed to get executable directory".to_string())?;
+    Ok(exe_dir.join(name))
+}
+
+/// Waits for a task to finish, aborting it after `SHUTDOWN_TIMEOUT`
+async fn stop_task(mut handle: JoinHandle<()>) {
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut handle)
+        .await
+        .is_err()
+    {
+        eprintln!("Task didn't stop in time, aborting it");
+        handle.abort();
+    }
+}
+
+/// Get the running monitor's timers
+async fn running_timers(state: &State<'_, AppState>) -> Result<Timers, String> {
+    state
+        .timers
+        .lock()
+        .await
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| "Not currently monitoring".to_string())
+}
+
 // Re-export Arc for use in start_monitoring
 use std::sync::Arc;
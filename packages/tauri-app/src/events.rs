@@ -0,0 +1,28 @@
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+use quarm_monitor::EventBus;
+
+/// Name of the frontend event every monitor event is emitted as
+pub const MONITOR_EVENT: &str = "monitor-event";
+
+/// Forwards monitor events to the frontend until every publisher is gone
+pub fn forward_events(app: AppHandle, events: &EventBus) -> JoinHandle<()> {
+    let mut receiver = events.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app.emit(MONITOR_EVENT, &event) {
+                        eprintln!("Failed to send monitor event to the UI: {}", e);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    eprintln!("UI fell behind, skipped {} monitor events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
@@ -2,6 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod events;
 mod state;
 
 use state::AppState;
@@ -16,8 +17,17 @@ fn main() {
             commands::init_tts,
             commands::test_announcement,
             commands::start_monitoring,
+            commands::start_replay,
+            commands::dry_run_report,
             commands::stop_monitoring,
             commands::get_monitoring_status,
+            commands::get_current_zone,
+            commands::get_lag_stats,
+            commands::list_timers,
+            commands::cancel_timer,
+            commands::cancel_all_timers,
+            commands::extend_timer,
+            commands::reset_timer,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,10 +1,11 @@
-use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
 use quarm_audio::TtsEngine;
 use quarm_config::Config;
+use quarm_monitor::{CancellationToken, LagTracker, Timers, ZoneTracker};
 
 /// Application state shared across Tauri commands
 pub struct AppState {
@@ -14,8 +15,18 @@ pub struct AppState {
     pub tts_engine: Arc<Mutex<Option<TtsEngine>>>,
     /// Handle to the log monitor task
     pub monitor_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Cancelled to ask the log monitor task to stop
+    pub shutdown: Arc<Mutex<Option<CancellationToken>>>,
+    /// Handle to the task forwarding monitor events to the UI
+    pub event_forwarder: Arc<Mutex<Option<JoinHandle<()>>>>,
     /// Flag indicating if monitoring is currently active
     pub is_monitoring: Arc<AtomicBool>,
+    /// Current zone of the monitored character, set while monitoring
+    pub zone_tracker: Arc<Mutex<Option<ZoneTracker>>>,
+    /// Timers of the running monitor, set while monitoring
+    pub timers: Arc<Mutex<Option<Timers>>>,
+    /// How far behind the logs the running monitor is, set while monitoring
+    pub lag: Arc<Mutex<Option<LagTracker>>>,
 }
 
 impl AppState {
@@ -25,7 +36,12 @@ impl AppState {
             config: Arc::new(Mutex::new(None)),
             tts_engine: Arc::new(Mutex::new(None)),
             monitor_handle: Arc::new(Mutex::new(None)),
+            shutdown: Arc::new(Mutex::new(None)),
+            event_forwarder: Arc::new(Mutex::new(None)),
             is_monitoring: Arc::new(AtomicBool::new(false)),
+            zone_tracker: Arc::new(Mutex::new(None)),
+            timers: Arc::new(Mutex::new(None)),
+            lag: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -1,5 +1,6 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { resolveResource } from '@tauri-apps/api/path';
 
 interface MessageConfig {
@@ -7,6 +8,10 @@ interface MessageConfig {
   pattern: string;
   announcement: string;
   timer_delay_in_seconds?: number;
+  category?: 'danger' | 'info' | 'tell';
+  pan?: number;
+  regex?: boolean;
+  zones?: { include?: string[]; exclude?: string[] };
 }
 
 interface Config {
@@ -14,11 +19,199 @@ interface Config {
   messages: MessageConfig[];
 }
 
+// Emitted by the backend as `monitor-event` while monitoring or replaying
+type MonitorEvent =
+  | { type: 'log_opened' | 'log_closed' | 'log_reset'; path: string; character: string }
+  | { type: 'zone_changed'; character: string; zone: string }
+  | {
+      type: 'line_matched';
+      trigger: number;
+      character: string;
+      timestamp: string | null;
+      body: string;
+      pattern: string;
+      announcement: string;
+    }
+  | { type: 'announcement_queued' | 'announcement_played'; text: string }
+  | { type: 'announcement_dropped'; text: string; reason: string }
+  | {
+      type: 'timer_scheduled';
+      id: number;
+      character: string;
+      pattern: string;
+      announcement: string;
+      delay_secs: number;
+      deadline: string;
+    }
+  | { type: 'timer_cancelled'; id: number; character: string; pattern: string }
+  | { type: 'timer_fired'; id: number; character: string; pattern: string; announcement: string }
+  | { type: 'error'; message: string };
+
+interface TriggerCount {
+  trigger: number;
+  pattern: string;
+  announcement: string;
+  matches: number;
+  timers_cancelled: number;
+}
+
+// Returned by `dry_run_report`; only the parts shown here
+interface DryRunReport {
+  log_file: string;
+  character: string;
+  lines: number;
+  matches: unknown[];
+  triggers: TriggerCount[];
+  never_matched: TriggerCount[];
+}
+
+interface FeedEntry {
+  time: Date;
+  text: string;
+}
+
+interface ActiveTimer {
+  character: string;
+  announcement: string;
+  delaySecs: number;
+  deadline: number;
+}
+
+// Number of entries kept in the live feed
+const FEED_LENGTH = 50;
+
+// Seconds added to a timer by its extend button
+const EXTEND_SECS = 30;
+
+// Speeds offered for replaying a recorded log
+const REPLAY_SPEEDS = ['1x', '2x', '10x', 'instant'];
+
+// How often a running replay is checked for having finished, in milliseconds
+const REPLAY_POLL_MS = 1000;
+
+// Describes the events worth showing in the live feed
+function describeEvent(event: MonitorEvent): string | null {
+  switch (event.type) {
+    case 'log_opened':
+      return `Following ${event.path}`;
+    case 'log_closed':
+      return `Stopped following ${event.path}`;
+    case 'log_reset':
+      return `Log reset, reading from the start: ${event.path}`;
+    case 'zone_changed':
+      return `${event.character} entered ${event.zone}`;
+    case 'line_matched':
+      return `${event.character}: ${event.body.trim()} -> "${event.announcement}"`;
+    case 'timer_scheduled':
+      return `Timer started: "${event.announcement}" in ${event.delay_secs}s`;
+    case 'timer_cancelled':
+      return `Timer cancelled: ${event.pattern}`;
+    case 'timer_fired':
+      return `Timer fired: "${event.announcement}"`;
+    case 'error':
+      return `Error: ${event.message}`;
+    default:
+      return null;
+  }
+}
+
+const inputStyle = {
+  marginRight: '8px',
+  padding: '5px',
+  fontSize: '14px',
+  border: '1px solid #ccc',
+  borderRadius: '4px',
+};
+
+const timerButtonStyle = {
+  marginLeft: '8px',
+  padding: '2px 8px',
+  fontSize: '12px',
+  border: '1px solid #ccc',
+  borderRadius: '4px',
+  backgroundColor: 'white',
+  cursor: 'pointer',
+};
+
 function App() {
   const [config, setConfig] = useState<Config | null>(null);
   const [isMonitoring, setIsMonitoring] = useState(false);
+  const [isReplaying, setIsReplaying] = useState(false);
+  const [logPath, setLogPath] = useState('');
+  const [replaySpeed, setReplaySpeed] = useState('10x');
+  const [replayStart, setReplayStart] = useState('');
+  const [replayEnd, setReplayEnd] = useState('');
+  const [report, setReport] = useState<DryRunReport | null>(null);
   const [status, setStatus] = useState('Idle');
   const [error, setError] = useState<string | null>(null);
+  const [feed, setFeed] = useState<FeedEntry[]>([]);
+  const [activeLogs, setActiveLogs] = useState<string[]>([]);
+  const [timers, setTimers] = useState<Record<number, ActiveTimer>>({});
+  const [now, setNow] = useState(Date.now());
+
+  // Follow the monitor's events for the live feed, active logs and timers
+  useEffect(() => {
+    const unlisten = listen<MonitorEvent>('monitor-event', ({ payload: event }) => {
+      const text = describeEvent(event);
+      if (text) {
+        setFeed((feed) => [{ time: new Date(), text }, ...feed].slice(0, FEED_LENGTH));
+      }
+
+      switch (event.type) {
+        case 'log_opened':
+          setActiveLogs((logs) => [...logs.filter((log) => log !== event.path), event.path]);
+          break;
+        case 'log_closed':
+          setActiveLogs((logs) => logs.filter((log) => log !== event.path));
+          break;
+        case 'timer_scheduled':
+          setTimers((timers) => ({
+            ...timers,
+            [event.id]: {
+              character: event.character,
+              announcement: event.announcement,
+              delaySecs: event.delay_secs,
+              deadline: Date.parse(event.deadline),
+            },
+          }));
+          break;
+        case 'timer_cancelled':
+        case 'timer_fired':
+          setTimers((timers) => {
+            const remaining = { ...timers };
+            delete remaining[event.id];
+            return remaining;
+          });
+          break;
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  // Tick countdown bars while any timer is running
+  const hasTimers = Object.keys(timers).length > 0;
+  useEffect(() => {
+    if (!hasTimers) return;
+    const interval = setInterval(() => setNow(Date.now()), 250);
+    return () => clearInterval(interval);
+  }, [hasTimers]);
+
+  // A replay stops by itself at the end of the log
+  useEffect(() => {
+    if (!isReplaying) return;
+    const interval = setInterval(async () => {
+      if (!(await invoke<boolean>('get_monitoring_status'))) {
+        setIsReplaying(false);
+        setIsMonitoring(false);
+        setActiveLogs([]);
+        setTimers({});
+        setStatus('Replay finished');
+      }
+    }, REPLAY_POLL_MS);
+    return () => clearInterval(interval);
+  }, [isReplaying]);
 
   const loadConfig = async () => {
     try {
@@ -36,19 +229,62 @@ function App() {
     }
   };
 
+  const initTts = async () => {
+    setStatus('Initializing TTS...');
+
+    // Resolve model path from bundled resources
+    const modelPath = await resolveResource('resources/speakers/en_US-amy-medium.onnx.json');
+
+    // Initialize TTS engine with resolved path
+    await invoke('init_tts', {
+      modelPath,
+    });
+  };
+
+  // Plays a recorded log through the triggers, stopped like live monitoring
+  const startReplay = async () => {
+    try {
+      setError(null);
+      await initTts();
+
+      setStatus('Starting replay...');
+      await invoke('start_replay', {
+        logPath,
+        speed: replaySpeed,
+        start: replayStart || null,
+        end: replayEnd || null,
+      });
+      setIsMonitoring(true);
+      setIsReplaying(true);
+      setStatus(`Replaying ${logPath}`);
+    } catch (e) {
+      const errorMsg = `Failed to start replay: ${e}`;
+      setError(errorMsg);
+      setStatus('Error');
+      console.error(errorMsg);
+    }
+  };
+
+  // Reports which triggers would have fired over the log, without playing anything
+  const dryRun = async () => {
+    try {
+      setError(null);
+      setStatus('Analyzing log...');
+      setReport(await invoke<DryRunReport>('dry_run_report', { logPath }));
+      setStatus('Dry run complete');
+    } catch (e) {
+      const errorMsg = `Failed to run dry run: ${e}`;
+      setError(errorMsg);
+      setStatus('Error');
+      console.error(errorMsg);
+    }
+  };
+
   const toggleMonitoring = async () => {
     try {
       if (!isMonitoring) {
-        setStatus('Initializing TTS...');
         setError(null);
-
-        // Resolve model path from bundled resources
-        const modelPath = await resolveResource('resources/speakers/en_US-amy-medium.onnx.json');
-
-        // Initialize TTS engine with resolved path
-        await invoke('init_tts', {
-          modelPath,
-        });
+        await initTts();
 
         // Start monitoring
         setStatus('Starting monitoring...');
@@ -59,7 +295,10 @@ function App() {
         setStatus('Stopping monitoring...');
         await invoke('stop_monitoring');
         setIsMonitoring(false);
-        setStatus('Monitoring stopped');
+        setIsReplaying(false);
+        setActiveLogs([]);
+        setTimers({});
+        setStatus(isReplaying ? 'Replay stopped' : 'Monitoring stopped');
       }
     } catch (e) {
       const errorMsg = `Failed to ${isMonitoring ? 'stop' : 'start'} monitoring: ${e}`;
@@ -69,6 +308,18 @@ function App() {
     }
   };
 
+  // Timer changes come back as monitor events, which update the countdowns
+  const manageTimer = async (command: string, args: Record<string, number> = {}) => {
+    try {
+      setError(null);
+      await invoke(command, args);
+    } catch (e) {
+      const errorMsg = `Failed to update timer: ${e}`;
+      setError(errorMsg);
+      console.error(errorMsg);
+    }
+  };
+
   const testAnnouncement = async (text: string) => {
     try {
       setError(null);
@@ -115,10 +366,201 @@ function App() {
             cursor: config ? 'pointer' : 'not-allowed',
           }}
         >
-          {isMonitoring ? 'Stop Monitoring' : 'Start Monitoring'}
+          {isMonitoring ? (isReplaying ? 'Stop Replay' : 'Stop Monitoring') : 'Start Monitoring'}
         </button>
       </div>
 
+      {/* Recorded Log */}
+      {config && !isMonitoring && (
+        <div style={{ marginBottom: '20px' }}>
+          <h2>Recorded Log</h2>
+          <div style={{ marginBottom: '10px' }}>
+            <input
+              value={logPath}
+              onChange={(e) => setLogPath(e.target.value)}
+              placeholder="Path to an eqlog_*.txt file"
+              style={{ ...inputStyle, width: '400px' }}
+            />
+          </div>
+          <div>
+            <select
+              value={replaySpeed}
+              onChange={(e) => setReplaySpeed(e.target.value)}
+              style={inputStyle}
+            >
+              {REPLAY_SPEEDS.map((speed) => (
+                <option key={speed} value={speed}>
+                  {speed}
+                </option>
+              ))}
+            </select>
+            <input
+              value={replayStart}
+              onChange={(e) => setReplayStart(e.target.value)}
+              placeholder="From (YYYY-MM-DD HH:MM:SS)"
+              style={inputStyle}
+            />
+            <input
+              value={replayEnd}
+              onChange={(e) => setReplayEnd(e.target.value)}
+              placeholder="To (YYYY-MM-DD HH:MM:SS)"
+              style={inputStyle}
+            />
+            <button
+              onClick={startReplay}
+              disabled={!logPath}
+              style={{
+                padding: '5px 10px',
+                fontSize: '14px',
+                backgroundColor: logPath ? '#007bff' : '#ccc',
+                color: 'white',
+                border: 'none',
+                borderRadius: '4px',
+                cursor: logPath ? 'pointer' : 'not-allowed',
+              }}
+            >
+              Replay
+            </button>
+            <button
+              onClick={dryRun}
+              disabled={!logPath}
+              style={{
+                marginLeft: '8px',
+                padding: '5px 10px',
+                fontSize: '14px',
+                backgroundColor: logPath ? '#6c757d' : '#ccc',
+                color: 'white',
+                border: 'none',
+                borderRadius: '4px',
+                cursor: logPath ? 'pointer' : 'not-allowed',
+              }}
+            >
+              Dry Run
+            </button>
+          </div>
+
+          {report && (
+            <div style={{ marginTop: '10px' }}>
+              <div style={{ marginBottom: '5px' }}>
+                <strong>{report.log_file}</strong> ({report.character}): {report.lines} lines,{' '}
+                {report.matches.length} matches
+              </div>
+              <table style={{ borderCollapse: 'collapse', fontSize: '14px' }}>
+                <thead>
+                  <tr>
+                    <th style={{ textAlign: 'left', paddingRight: '16px' }}>Trigger</th>
+                    <th style={{ textAlign: 'right', paddingRight: '16px' }}>Matches</th>
+                    <th style={{ textAlign: 'right' }}>Timers cancelled</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {report.triggers.map((count) => (
+                    <tr
+                      key={count.trigger}
+                      style={{ color: count.matches === 0 ? '#999' : undefined }}
+                    >
+                      <td style={{ paddingRight: '16px' }}>
+                        {count.pattern || '(any line)'} -&gt; "{count.announcement}"
+                      </td>
+                      <td style={{ textAlign: 'right', paddingRight: '16px' }}>
+                        {count.matches}
+                      </td>
+                      <td style={{ textAlign: 'right' }}>{count.timers_cancelled}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+              {report.never_matched.length > 0 && (
+                <div style={{ marginTop: '5px', color: '#666' }}>
+                  {report.never_matched.length} of {report.triggers.length} triggers never
+                  matched
+                </div>
+              )}
+            </div>
+          )}
+        </div>
+      )}
+
+      {/* Live Activity */}
+      {isMonitoring && (
+        <div style={{ marginBottom: '20px' }}>
+          <h2>Activity</h2>
+          <div style={{ marginBottom: '10px' }}>
+            <strong>Following:</strong>{' '}
+            {activeLogs.length > 0 ? activeLogs.join(', ') : 'no log yet'}
+          </div>
+
+          {hasTimers && (
+            <button
+              onClick={() => manageTimer('cancel_all_timers')}
+              style={{ ...timerButtonStyle, marginBottom: '8px' }}
+            >
+              Cancel all timers
+            </button>
+          )}
+          {Object.entries(timers).map(([id, timer]) => {
+            const remaining = Math.max(0, timer.deadline - now) / 1000;
+            const fraction = timer.delaySecs > 0 ? remaining / timer.delaySecs : 0;
+            return (
+              <div key={id} style={{ marginBottom: '8px' }}>
+                <div>
+                  {timer.announcement} ({timer.character}) - {Math.ceil(remaining)}s
+                  <button
+                    onClick={() =>
+                      manageTimer('extend_timer', { id: Number(id), seconds: EXTEND_SECS })
+                    }
+                    style={timerButtonStyle}
+                  >
+                    +{EXTEND_SECS}s
+                  </button>
+                  <button
+                    onClick={() => manageTimer('reset_timer', { id: Number(id) })}
+                    style={timerButtonStyle}
+                  >
+                    Reset
+                  </button>
+                  <button
+                    onClick={() => manageTimer('cancel_timer', { id: Number(id) })}
+                    style={timerButtonStyle}
+                  >
+                    Cancel
+                  </button>
+                </div>
+                <div style={{ height: '8px', backgroundColor: '#eee', borderRadius: '4px' }}>
+                  <div
+                    style={{
+                      width: `${Math.min(1, fraction) * 100}%`,
+                      height: '100%',
+                      backgroundColor: '#007bff',
+                      borderRadius: '4px',
+                    }}
+                  />
+                </div>
+              </div>
+            );
+          })}
+
+          <div
+            style={{
+              maxHeight: '200px',
+              overflowY: 'auto',
+              padding: '10px',
+              border: '1px solid #ddd',
+              borderRadius: '4px',
+              fontFamily: 'monospace',
+              fontSize: '13px',
+            }}
+          >
+            {feed.length === 0 && <div style={{ color: '#666' }}>Waiting for matches...</div>}
+            {feed.map((entry, idx) => (
+              <div key={idx}>
+                [{entry.time.toLocaleTimeString()}] {entry.text}
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       {/* Configuration Display */}
       {config ? (
         <div>
@@ -146,6 +588,11 @@ function App() {
                       (Delay: {msg.timer_delay_in_seconds}s)
                     </span>
                   )}
+                  {msg.category && (
+                    <span style={{ marginLeft: '10px', color: '#666' }}>
+                      (Category: {msg.category})
+                    </span>
+                  )}
                 </div>
                 <div style={{ marginBottom: '5px' }}>
                   <strong>Pattern:</strong> {msg.pattern}